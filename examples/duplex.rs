@@ -0,0 +1,47 @@
+extern crate rust_coreaudio;
+use rust_coreaudio::stream;
+use rust_coreaudio::utils;
+
+const CHANNELS: u32 = 2;
+const VOLUME: f32 = 0.5;
+
+// Play the captured data with a lower volume. The missing input channels are
+// filled with silence.
+fn pass_through(
+    input: stream::InputCallbackArgs<f32>,
+    output: stream::CallbackArgs<f32>,
+    _timing: &stream::Timing,
+) {
+    for (channel, buffer) in output.iter_mut().enumerate() {
+        match input.get(channel) {
+            Some(data) => {
+                for (out, data) in buffer.iter_mut().zip(data.iter()) {
+                    *out = data * VOLUME;
+                }
+            }
+            None => {
+                for out in buffer.iter_mut() {
+                    *out = 0.0;
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    use stream::DuplexStream;
+
+    println!("Play the input from the default input device");
+    // The input data isn't resampled, so run at the rate of the input device.
+    let device = utils::get_default_device(&utils::Scope::Input).unwrap();
+    let rate = device.get_rate(&utils::Scope::Input).unwrap();
+    let stm = DuplexStream::new(CHANNELS, CHANNELS, rate, pass_through).unwrap();
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(3000));
+    stm.stop().unwrap();
+    println!(
+        "Overruns: {}, underruns: {}",
+        stm.overruns(),
+        stm.underruns()
+    );
+}
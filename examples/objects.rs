@@ -0,0 +1,63 @@
+extern crate rust_coreaudio;
+use rust_coreaudio::utils;
+
+fn print_object_tree(object: &utils::AudioObject, depth: usize) {
+    let class = object
+        .get_class()
+        .map(|class| format!("{:?}", class))
+        .unwrap_or_else(|e| format!("{:?}", e));
+    let name = object.get_name().unwrap_or_default();
+    println!("{}{} {} {}", "  ".repeat(depth), object, class, name);
+    for owned in object.get_owned_objects().unwrap_or_default() {
+        print_object_tree(&owned, depth + 1);
+    }
+}
+
+fn print_plug_ins() {
+    for plug_in in utils::get_plug_ins().unwrap() {
+        println!(
+            "plug-in {}: {}, devices: {:?}, boxes: {}, clock devices: {}",
+            *plug_in,
+            plug_in.get_bundle_id().unwrap_or_default(),
+            plug_in
+                .get_devices()
+                .unwrap_or_default()
+                .iter()
+                .map(|device| device.to_string())
+                .collect::<Vec<String>>(),
+            plug_in.get_boxes().unwrap_or_default().len(),
+            plug_in.get_clock_devices().unwrap_or_default().len(),
+        );
+    }
+}
+
+fn print_boxes() {
+    for audio_box in utils::get_boxes().unwrap() {
+        println!(
+            "box {}: {} ({}), acquired: {:?}",
+            *audio_box,
+            audio_box.get_name().unwrap_or_default(),
+            audio_box.get_uid().unwrap_or_default(),
+            audio_box.is_acquired(),
+        );
+    }
+}
+
+fn print_clock_devices() {
+    for clock_device in utils::get_clock_devices().unwrap() {
+        println!(
+            "clock device {}: {} ({}), domain: {:?}",
+            *clock_device,
+            clock_device.get_name().unwrap_or_default(),
+            clock_device.get_uid().unwrap_or_default(),
+            clock_device.get_clock_domain(),
+        );
+    }
+}
+
+fn main() {
+    print_object_tree(&utils::get_system_object(), 0);
+    print_plug_ins();
+    print_boxes();
+    print_clock_devices();
+}
@@ -0,0 +1,48 @@
+extern crate rust_coreaudio;
+use rust_coreaudio::stream;
+use rust_coreaudio::utils;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+const CHANNELS: u32 = 1;
+
+// Raise the peak of each channel to the peak of the captured data. The peaks
+// are the bits of the non-negative `f32`s, which are ordered as the values, so
+// they're updated without a lock or an allocation on the audio thread.
+fn measure_peaks(buffers: stream::InputCallbackArgs<f32>, peaks: &[AtomicU32]) {
+    // buffers.len() is equal to channels!
+    for (buffer, peak) in buffers.iter().zip(peaks.iter()) {
+        let max = buffer
+            .iter()
+            .fold(0.0, |max: f32, data| max.max(data.abs()));
+        peak.fetch_max(max.to_bits(), Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    use stream::InputStream;
+
+    println!("Record from the default input device");
+    // The input data isn't resampled, so capture at the rate of the device.
+    let device = utils::get_default_device(&utils::Scope::Input).unwrap();
+    let rate = device.get_rate(&utils::Scope::Input).unwrap();
+    // Allocate the peaks before the stream starts.
+    let peaks: Arc<Vec<AtomicU32>> = Arc::new((0..CHANNELS).map(|_| AtomicU32::new(0)).collect());
+    let captured = peaks.clone();
+    let stm = InputStream::<f32>::new(CHANNELS, rate, move |buffers, _timing| {
+        measure_peaks(buffers, &captured);
+    })
+    .unwrap();
+    stm.start().unwrap();
+    // Take the peaks of every 100 ms on this thread.
+    for _ in 0..10 {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let taken: Vec<f32> = peaks
+            .iter()
+            .map(|peak| f32::from_bits(peak.swap(0, Ordering::SeqCst)))
+            .collect();
+        println!("Peaks: {:?}", taken);
+    }
+    stm.stop().unwrap();
+}
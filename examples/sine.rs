@@ -1,24 +1,6 @@
-// Introduce `lazy_static` to use a mutable global variable.
-#[macro_use]
-extern crate lazy_static;
-// It needs to wrap a `Mutex` around the global variable to make it mutable.
-use std::sync::Mutex;
-
 extern crate rust_coreaudio;
 use rust_coreaudio::stream;
 
-struct SynthesizedData(f64);
-impl From<SynthesizedData> for f32 {
-    fn from(d: SynthesizedData) -> f32 {
-        d.0 as f32
-    }
-}
-impl From<SynthesizedData> for i16 {
-    fn from(d: SynthesizedData) -> i16 {
-        (d.0 * 32767.0) as i16
-    }
-}
-
 struct Synthesizer {
     channels: u32,
     rate: f64,
@@ -36,20 +18,18 @@ impl Synthesizer {
         }
     }
 
-    fn run<T>(&mut self, buffers: stream::CallbackArgs<T>)
-    where
-        T: std::convert::From<SynthesizedData>,
-    {
+    // Return the number of the frames synthesized.
+    fn run<T: stream::Sample>(&mut self, buffers: stream::CallbackArgs<T>) -> usize {
         assert_eq!(self.channels, buffers.len() as u32);
         // buffers.len() is equal to channels!
         for (channel, buffer) in buffers.iter_mut().enumerate() {
             // buffer.len() is equal to frames!
             for data in buffer.iter_mut() {
-                let channel_data = SynthesizedData(self.phase[channel].sin() * self.volume);
-                *data = channel_data.into();
+                *data = T::from_f64(self.phase[channel].sin() * self.volume);
                 self.phase[channel as usize] += self.get_increment(channel as u32);
             }
         }
+        buffers[0].len()
     }
 
     fn get_increment(&self, channel: u32) -> f64 {
@@ -62,36 +42,32 @@ impl Synthesizer {
 const CHANNELS: u32 = 2;
 const RATE: f64 = 44_100.0;
 const VOLUME: f64 = 0.5;
-lazy_static! {
-    static ref SYNTHESIZER: Mutex<Synthesizer> =
-        Mutex::new(Synthesizer::new(CHANNELS, RATE, VOLUME));
-}
-
-fn fill_buffer_float(buffers: stream::CallbackArgs<f32>) {
-    SYNTHESIZER.lock().unwrap().run(buffers);
-}
 
+// The synthesizer is owned by the callback, so it's unnecessary to put it in
+// a global variable and lock it in the callback.
 fn play_sound_float() {
-    use stream::{Format, Stream};
+    use stream::Stream;
 
     println!("Play `float` stream");
-    let mut stm = Stream::new(CHANNELS, Format::F32LE, RATE, fill_buffer_float).unwrap();
-    stm.init().unwrap();
+    let mut synthesizer = Synthesizer::new(CHANNELS, RATE, VOLUME);
+    let stm = Stream::<f32>::new(CHANNELS, RATE, move |buffers, _timing| {
+        synthesizer.run(buffers)
+    })
+    .unwrap();
     stm.start().unwrap();
     std::thread::sleep(std::time::Duration::from_millis(1000));
     stm.stop().unwrap();
 }
 
-fn fill_buffer_short(buffers: stream::CallbackArgs<i16>) {
-    SYNTHESIZER.lock().unwrap().run(buffers);
-}
-
 fn play_sound_short() {
-    use stream::{Format, Stream};
+    use stream::Stream;
 
     println!("Play `short` stream");
-    let mut stm = Stream::new(CHANNELS, Format::S16LE, RATE, fill_buffer_short).unwrap();
-    stm.init().unwrap();
+    let mut synthesizer = Synthesizer::new(CHANNELS, RATE, VOLUME);
+    let stm = Stream::<i16>::new(CHANNELS, RATE, move |buffers, _timing| {
+        synthesizer.run(buffers)
+    })
+    .unwrap();
     stm.start().unwrap();
     std::thread::sleep(std::time::Duration::from_millis(1000));
     stm.stop().unwrap();
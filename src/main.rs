@@ -1,5 +1,8 @@
 fn main() {
     println!("Commands:");
     println!("$ cargo run --example sine\t: play sine wave");
+    println!("$ cargo run --example record\t: show the peaks of the captured data");
+    println!("$ cargo run --example duplex\t: pass the input through to the output");
     println!("$ cargo run --example devices\t: show devices information");
+    println!("$ cargo run --example objects\t: show audio objects, plug-ins and boxes");
 }
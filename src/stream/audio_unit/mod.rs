@@ -8,19 +8,34 @@ use std::ptr; // For ptr::null_mut()
 // Using PartialEq for comparison.
 #[derive(PartialEq)]
 pub enum Error {
+    FormatNotSupported,
     InvalidComponentID,
+    InvalidElement,
+    InvalidParameter,
+    InvalidProperty,
+    InvalidPropertyValue,
+    InvalidScope,
     NoComponentFound,
     PropertyNotWritable,
     Uninitialized,
+    // The status without a specific variant, e.g., the errors from the device
+    // of the unit once the device is gone.
+    Other(sys::OSStatus),
 }
 
 impl From<sys::OSStatus> for Error {
     fn from(status: sys::OSStatus) -> Self {
         match status {
             -3000 => Error::InvalidComponentID, // https://developer.apple.com/documentation/coreservices/1559940-anonymous/invalidcomponentid?language=objc
+            sys::kAudioUnitErr_FormatNotSupported => Error::FormatNotSupported,
+            sys::kAudioUnitErr_InvalidElement => Error::InvalidElement,
+            sys::kAudioUnitErr_InvalidParameter => Error::InvalidParameter,
+            sys::kAudioUnitErr_InvalidProperty => Error::InvalidProperty,
+            sys::kAudioUnitErr_InvalidPropertyValue => Error::InvalidPropertyValue,
+            sys::kAudioUnitErr_InvalidScope => Error::InvalidScope,
             sys::kAudioUnitErr_PropertyNotWritable => Error::PropertyNotWritable,
             sys::kAudioUnitErr_Uninitialized => Error::Uninitialized,
-            s => panic!("Unknown status: {}", s),
+            s => Error::Other(s),
         }
     }
 }
@@ -28,10 +43,17 @@ impl From<sys::OSStatus> for Error {
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let printable = match self {
+            Error::FormatNotSupported => "The stream format is not supported.",
             Error::InvalidComponentID => "Using the invalid audio component.",
+            Error::InvalidElement => "Using the invalid element.",
+            Error::InvalidParameter => "Using the invalid parameter.",
+            Error::InvalidProperty => "Using the invalid property.",
+            Error::InvalidPropertyValue => "Setting the invalid value to the property.",
+            Error::InvalidScope => "Using the invalid scope.",
             Error::NoComponentFound => "No audio component matching with provided settings.",
             Error::PropertyNotWritable => "Trying to write a non-writable property.",
             Error::Uninitialized => "Trying to run an uninitialized AudioUnit.",
+            Error::Other(status) => return write!(f, "Failed with status {}.", status),
         };
         write!(f, "{}", printable)
     }
@@ -47,11 +69,19 @@ pub enum Element {
 pub struct AudioUnit(sys::AudioUnit);
 
 impl AudioUnit {
-    pub fn new() -> Result<AudioUnit, Error> {
-        let unit = create_unit()?;
+    // The AUHAL unit, which can do input and work with any device.
+    pub fn new_hal() -> Result<AudioUnit, Error> {
+        let unit = create_unit(sys::kAudioUnitSubType_HALOutput)?;
         Ok(AudioUnit(unit))
     }
 
+    // A unit without an instance, so all its operations fail. It's used to
+    // test how the failures of the unit are handled.
+    #[cfg(test)]
+    pub fn new_invalid() -> AudioUnit {
+        AudioUnit(ptr::null_mut())
+    }
+
     pub fn get_property_info(
         &self,
         id: sys::AudioUnitPropertyID,
@@ -80,6 +110,58 @@ impl AudioUnit {
         set_property::<T>(self.0, id, scope, element, data)
     }
 
+    // Only the tests check the parameters now.
+    #[cfg(test)]
+    pub fn get_parameter(
+        &self,
+        id: sys::AudioUnitParameterID,
+        scope: sys::AudioUnitScope,
+        element: Element,
+    ) -> Result<f32, Error> {
+        let mut value: sys::AudioUnitParameterValue = 0.0;
+        let status = unsafe {
+            sys::AudioUnitGetParameter(
+                self.0,
+                id,
+                scope,
+                element as sys::AudioUnitElement,
+                &mut value,
+            )
+        };
+        convert_to_result(status)?;
+        Ok(value)
+    }
+
+    pub fn set_parameter(
+        &self,
+        id: sys::AudioUnitParameterID,
+        scope: sys::AudioUnitScope,
+        element: Element,
+        value: f32,
+    ) -> Result<(), Error> {
+        set_parameter(self.0, id, scope, element, value)
+    }
+
+    // Return the status directly since this is called within the audio
+    // callbacks, where the status is passed back to the system.
+    pub fn render(
+        &self,
+        flags: *mut sys::AudioUnitRenderActionFlags,
+        time_stamp: *const sys::AudioTimeStamp,
+        element: Element,
+        frames: u32,
+        data: *mut sys::AudioBufferList,
+    ) -> sys::OSStatus {
+        audio_unit_render(
+            self.0,
+            flags,
+            time_stamp,
+            element as sys::UInt32,
+            frames,
+            data,
+        )
+    }
+
     pub fn initialize(&self) -> Result<(), Error> {
         init_unit(self.0)
     }
@@ -97,17 +179,19 @@ impl AudioUnit {
     }
 }
 
+// Don't panic here since the unit may fail to stop once its device is gone,
+// or the unit may be dropped while unwinding.
 impl Drop for AudioUnit {
     fn drop(&mut self) {
-        assert!(self.stop().is_ok());
-        assert!(self.uninitialize().is_ok());
+        let _ = self.stop();
+        let _ = self.uninitialize();
     }
 }
 
-fn create_unit() -> Result<sys::AudioUnit, Error> {
+fn create_unit(sub_type: sys::OSType) -> Result<sys::AudioUnit, Error> {
     let desc = sys::AudioComponentDescription {
         componentType: sys::kAudioUnitType_Output,
-        componentSubType: sys::kAudioUnitSubType_DefaultOutput,
+        componentSubType: sub_type,
         componentManufacturer: sys::kAudioUnitManufacturer_Apple,
         componentFlags: 0,
         componentFlagsMask: 0,
@@ -198,6 +282,17 @@ fn set_property<T>(
     convert_to_result(status)
 }
 
+fn set_parameter(
+    unit: sys::AudioUnit,
+    id: sys::AudioUnitParameterID,
+    scope: sys::AudioUnitScope,
+    element: Element,
+    value: f32,
+) -> Result<(), Error> {
+    let status = audio_unit_set_parameter(unit, id, scope, element as sys::AudioUnitElement, value);
+    convert_to_result(status)
+}
+
 fn find_next_component(
     component: sys::AudioComponent,
     description: &sys::AudioComponentDescription,
@@ -286,6 +381,28 @@ fn audio_unit_set_property<T>(
     }
 }
 
+// The parameter is changed immediately, so the buffer offset is always 0.
+fn audio_unit_set_parameter(
+    unit: sys::AudioUnit,
+    id: sys::AudioUnitParameterID,
+    scope: sys::AudioUnitScope,
+    element: sys::AudioUnitElement,
+    value: sys::AudioUnitParameterValue,
+) -> sys::OSStatus {
+    unsafe { sys::AudioUnitSetParameter(unit, id, scope, element, value, 0) }
+}
+
+fn audio_unit_render(
+    unit: sys::AudioUnit,
+    flags: *mut sys::AudioUnitRenderActionFlags,
+    time_stamp: *const sys::AudioTimeStamp,
+    bus: sys::UInt32,
+    frames: sys::UInt32,
+    data: *mut sys::AudioBufferList,
+) -> sys::OSStatus {
+    unsafe { sys::AudioUnitRender(unit, flags, time_stamp, bus, frames, data) }
+}
+
 fn audio_component_find_next(
     component: sys::AudioComponent,
     description: &sys::AudioComponentDescription,
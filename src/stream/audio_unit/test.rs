@@ -5,7 +5,26 @@ use super::*;
 
 // struct AudioUnit
 // ------------------------------------
-// Skip now ...
+#[test]
+fn test_drop_invalid_unit() {
+    let unit = AudioUnit::new_invalid();
+    assert!(unit.stop().is_err());
+    assert!(unit.uninitialize().is_err());
+    // Dropping the unit that fails to stop doesn't panic.
+    drop(unit);
+}
+
+// Error::from
+// ------------------------------------
+#[test]
+fn test_error_from_unknown_status() {
+    let status = sys::kAudioHardwareBadDeviceError as sys::OSStatus;
+    assert_eq!(Error::from(status), Error::Other(status));
+    assert_eq!(
+        Error::from(sys::kAudioUnitErr_Uninitialized),
+        Error::Uninitialized
+    );
+}
 
 // Tests for Private Functions
 // ============================================================================
@@ -14,8 +33,10 @@ use super::*;
 // ------------------------------------
 #[test]
 fn test_create_unit() {
-    let output = create_unit().unwrap();
+    let output = create_unit(sys::kAudioUnitSubType_DefaultOutput).unwrap();
     assert!(!output.is_null());
+    let hal = create_unit(sys::kAudioUnitSubType_HALOutput).unwrap();
+    assert!(!hal.is_null());
 }
 
 // get_property_info
@@ -30,6 +51,10 @@ fn test_create_unit() {
 // ------------------------------------
 // Skip now ...
 
+// set_parameter
+// ------------------------------------
+// Skip now ...
+
 // find_next_component
 // ------------------------------------
 #[test]
@@ -101,6 +126,10 @@ fn test_get_new_instance() {
 // ------------------------------------
 // Skip now ...
 
+// audio_unit_set_parameter
+// ------------------------------------
+// Skip now ...
+
 // audio_component_find_next
 // ------------------------------------
 // Skip now ...
@@ -0,0 +1,94 @@
+extern crate coreaudio_sys as sys;
+
+use std::os::raw::c_void;
+
+use super::audio_unit::{AudioUnit, Element};
+use super::{Error, Parameters};
+use utils::{AudioObject, GetObjectId};
+
+// The helpers to set up the AUHAL units. See below link for the details:
+// https://developer.apple.com/library/archive/technotes/tn2091/_index.html
+
+// Enable or disable the input on the input element and the output on the
+// output element of an AUHAL unit.
+pub fn enable_io(unit: &AudioUnit, input: bool, output: bool) -> Result<(), Error> {
+    let input = input as u32;
+    unit.set_property(
+        sys::kAudioOutputUnitProperty_EnableIO,
+        sys::kAudioUnitScope_Input,
+        Element::Input,
+        &input,
+    )?;
+    let output = output as u32;
+    unit.set_property(
+        sys::kAudioOutputUnitProperty_EnableIO,
+        sys::kAudioUnitScope_Output,
+        Element::Output,
+        &output,
+    )?;
+    Ok(())
+}
+
+pub fn set_device(unit: &AudioUnit, device: &AudioObject) -> Result<(), Error> {
+    let id: sys::AudioObjectID = device.get_id();
+    unit.set_property(
+        sys::kAudioOutputUnitProperty_CurrentDevice,
+        sys::kAudioUnitScope_Global,
+        Element::Output,
+        &id,
+    )?;
+    Ok(())
+}
+
+// The format of the data we get is set on the output scope of the input
+// element.
+pub fn set_input_format(unit: &AudioUnit, parameters: &Parameters) -> Result<(), Error> {
+    let description = parameters.to_description();
+    unit.set_property(
+        sys::kAudioUnitProperty_StreamFormat,
+        sys::kAudioUnitScope_Output,
+        Element::Input,
+        &description,
+    )?;
+    Ok(())
+}
+
+// The format of the data we provide is set on the input scope of the output
+// element.
+pub fn set_output_format(unit: &AudioUnit, parameters: &Parameters) -> Result<(), Error> {
+    let description = parameters.to_description();
+    unit.set_property(
+        sys::kAudioUnitProperty_StreamFormat,
+        sys::kAudioUnitScope_Input,
+        Element::Output,
+        &description,
+    )?;
+    Ok(())
+}
+
+pub fn set_input_callback(
+    unit: &AudioUnit,
+    callback: sys::AURenderCallback,
+    data: *mut c_void,
+) -> Result<(), Error> {
+    let callback_struct = sys::AURenderCallbackStruct {
+        inputProc: callback,
+        inputProcRefCon: data,
+    };
+    unit.set_property(
+        sys::kAudioOutputUnitProperty_SetInputCallback,
+        sys::kAudioUnitScope_Global,
+        Element::Output,
+        &callback_struct,
+    )?;
+    Ok(())
+}
+
+pub fn get_maximum_frames(unit: &AudioUnit) -> Result<usize, Error> {
+    let frames: u32 = unit.get_property(
+        sys::kAudioUnitProperty_MaximumFramesPerSlice,
+        sys::kAudioUnitScope_Global,
+        Element::Output,
+    )?;
+    Ok(frames as usize)
+}
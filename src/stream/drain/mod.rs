@@ -0,0 +1,51 @@
+// Track the frames that are yet to be heard after the callback signals the
+// end of the stream by producing fewer frames than requested. The produced
+// frames are heard after the output latency, so the stream is drained only
+// when the time of both has passed.
+pub struct Drain {
+    // The frames to be rendered before the stream is drained, counted from
+    // the start of the next buffer. It's `None` before the end of the stream.
+    remaining: Option<usize>,
+}
+
+impl Drain {
+    pub fn new() -> Self {
+        Drain { remaining: None }
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.remaining.is_some()
+    }
+
+    // Forget the end of the stream when the stream restarts.
+    pub fn reset(&mut self) {
+        self.remaining = None;
+    }
+
+    // Called when the callback only produces `produced` frames of the
+    // `frames` frames requested. The `latency` is the output latency in
+    // frames of the device in use.
+    pub fn start(&mut self, produced: usize, frames: usize, latency: usize) {
+        assert!(produced <= frames);
+        self.remaining = Some((produced + latency).saturating_sub(frames));
+    }
+
+    // Called at the start of each buffer after the end of the stream, which
+    // has `frames` frames. Return `true` once all the produced frames have
+    // been heard.
+    pub fn advance(&mut self, frames: usize) -> bool {
+        match self.remaining {
+            Some(0) => true,
+            Some(remaining) => {
+                self.remaining = Some(remaining.saturating_sub(frames));
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
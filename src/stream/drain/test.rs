@@ -0,0 +1,67 @@
+use super::*;
+
+// Tests for Public Functions
+// ============================================================================
+
+// Drain::new
+// Drain::is_draining
+// ------------------------------------
+#[test]
+fn test_new() {
+    let mut drain = Drain::new();
+    assert!(!drain.is_draining());
+    assert!(!drain.advance(512));
+}
+
+// Drain::start
+// Drain::advance
+// ------------------------------------
+#[test]
+fn test_drain_without_latency() {
+    let mut drain = Drain::new();
+    drain.start(100, 512, 0);
+    assert!(drain.is_draining());
+    // The produced frames are heard before the next buffer starts.
+    assert!(drain.advance(512));
+    assert!(drain.advance(512));
+}
+
+#[test]
+fn test_drain_with_latency() {
+    let mut drain = Drain::new();
+    drain.start(100, 512, 1000);
+    // 100 + 1000 - 512 = 588 frames are left when the next buffer starts.
+    assert!(!drain.advance(512));
+    // 76 frames are left.
+    assert!(!drain.advance(512));
+    assert!(drain.advance(512));
+}
+
+#[test]
+fn test_drain_with_nothing_produced() {
+    let mut drain = Drain::new();
+    drain.start(0, 512, 512);
+    assert!(drain.advance(256));
+}
+
+#[test]
+#[should_panic]
+fn test_start_with_too_many_frames() {
+    let mut drain = Drain::new();
+    drain.start(513, 512, 0);
+}
+
+// Drain::reset
+// ------------------------------------
+#[test]
+fn test_reset() {
+    let mut drain = Drain::new();
+    drain.start(0, 512, 0);
+    assert!(drain.advance(512));
+    drain.reset();
+    assert!(!drain.is_draining());
+    assert!(!drain.advance(512));
+    // The stream can end again.
+    drain.start(100, 512, 0);
+    assert!(drain.advance(512));
+}
@@ -0,0 +1,392 @@
+extern crate coreaudio_sys as sys;
+
+use std::cell::UnsafeCell;
+use std::mem::size_of;
+use std::os::raw::c_void;
+use std::slice;
+use std::sync::Arc;
+
+use super::audio_unit::{AudioUnit, Element};
+use super::auhal::{
+    enable_io, get_maximum_frames, set_device, set_input_callback, set_input_format,
+    set_output_format,
+};
+use super::fifo::{Fifo, FifoReader, FifoWriter};
+use super::input_stream::{CaptureBuffers, InputCallbackArgs};
+use super::slice_table::SliceTable;
+use super::timing::get_timing;
+use super::{check_input_rate, check_scope, CallbackArgs, Error, Parameters, Sample, Timing};
+use utils::{get_default_device, AudioObject, Scope};
+
+type DuplexCallback<T> = Box<dyn FnMut(InputCallbackArgs<T>, CallbackArgs<T>, &Timing) + Send>;
+
+// How many times of the maximum frames per callback can be buffered between
+// the input and output devices.
+const FIFO_CAPACITY_IN_CALLBACKS: usize = 4;
+
+// The callback gets the captured input data and the output buffers with the
+// same number of frames. The `Timing` tells when the data in the output
+// buffers will be played.
+//
+// When the input and output are on the same device, one AUHAL unit does both
+// and the input data is pulled within the output callback. Otherwise, another
+// AUHAL unit captures the input data into a `Fifo`, which is read in the
+// output callback. See `Fifo` for how the drift between the two devices is
+// handled. See the `Stream` for why the callback contexts are boxed.
+pub struct DuplexStream<T: Sample> {
+    input_device: AudioObject,
+    output_device: AudioObject,
+    context: Box<DuplexContext<T>>,
+    // The input side and its queue on different devices.
+    capture: Option<Box<CaptureContext<T>>>,
+    fifo: Option<Arc<Fifo<T>>>,
+}
+
+// The context of the output callback. The unit is shared with the thread
+// owning the stream. The rest is in `render_state`, only touched by the
+// output callback once the callback is set.
+struct DuplexContext<T> {
+    input_parameters: Parameters,
+    output_parameters: Parameters,
+    unit: AudioUnit,
+    render_state: UnsafeCell<DuplexRenderState<T>>,
+}
+
+struct DuplexRenderState<T> {
+    callback: DuplexCallback<T>,
+    input: DuplexInput<T>,
+    output: SliceTable<T>,
+}
+
+// Where the output callback gets the input data.
+enum DuplexInput<T> {
+    // Pulled from the input element of the same unit.
+    Unit(CaptureBuffers<T>),
+    // Read from the `Fifo` filled by the unit on the input device, through
+    // the buffers allocated for the maximum frames of the output unit.
+    Fifo {
+        reader: FifoReader<T>,
+        buffers: Vec<Vec<T>>,
+        slices: SliceTable<T>,
+    },
+}
+
+impl<T: Sample> DuplexStream<T> {
+    // Use the default input and output devices.
+    pub fn new<F>(
+        input_channels: u32,
+        output_channels: u32,
+        rate: f64,
+        callback: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(InputCallbackArgs<T>, CallbackArgs<T>, &Timing) + Send + 'static,
+    {
+        let input_device = get_default_device(&Scope::Input)?;
+        let output_device = get_default_device(&Scope::Output)?;
+        Self::with_devices(
+            &input_device,
+            input_channels,
+            &output_device,
+            output_channels,
+            rate,
+            callback,
+        )
+    }
+
+    // Return `Error::UnsupportedRate` if the rate isn't the nominal rate of
+    // the input device.
+    pub fn with_devices<F>(
+        input_device: &AudioObject,
+        input_channels: u32,
+        output_device: &AudioObject,
+        output_channels: u32,
+        rate: f64,
+        callback: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(InputCallbackArgs<T>, CallbackArgs<T>, &Timing) + Send + 'static,
+    {
+        check_scope(input_device, &Scope::Input)?;
+        check_scope(output_device, &Scope::Output)?;
+        check_input_rate(input_device, rate)?;
+        let input_parameters = Parameters::new(input_channels, T::FORMAT, rate);
+        let output_parameters = Parameters::new(output_channels, T::FORMAT, rate);
+        let output = SliceTable::new(output_channels as usize);
+        let unit = AudioUnit::new_hal()?;
+        let mut stm = DuplexStream {
+            input_device: input_device.clone(),
+            output_device: output_device.clone(),
+            context: Box::new(DuplexContext {
+                input_parameters,
+                output_parameters,
+                unit,
+                render_state: UnsafeCell::new(DuplexRenderState {
+                    callback: Box::new(callback),
+                    input: DuplexInput::Unit(CaptureBuffers::new()),
+                    output,
+                }),
+            }),
+            capture: None,
+            fifo: None,
+        };
+        stm.init()?;
+        Ok(stm)
+    }
+
+    pub fn is_same_device(&self) -> bool {
+        self.input_device == self.output_device
+    }
+
+    // The capture unit is stopped again if the output unit fails to start.
+    pub fn start(&self) -> Result<(), Error> {
+        if let Some(ref capture) = self.capture {
+            capture.unit.start()?;
+        }
+        if let Err(e) = self.context.unit.start() {
+            if let Some(ref capture) = self.capture {
+                let _ = capture.unit.stop();
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    // Both units are stopped even if one of them fails.
+    pub fn stop(&self) -> Result<(), Error> {
+        let stopped = self.context.unit.stop();
+        if let Some(ref capture) = self.capture {
+            capture.unit.stop()?;
+        }
+        stopped?;
+        Ok(())
+    }
+
+    // The number of the times the input data is dropped since the input
+    // device runs faster than the output device. Always 0 on the same device.
+    pub fn overruns(&self) -> usize {
+        self.fifo.as_ref().map_or(0, |fifo| fifo.get_overruns())
+    }
+
+    // The number of the times the input data is filled with silence since
+    // the output device runs faster than the input device. Always 0 on the
+    // same device.
+    pub fn underruns(&self) -> usize {
+        self.fifo.as_ref().map_or(0, |fifo| fifo.get_underruns())
+    }
+
+    fn init(&mut self) -> Result<(), Error> {
+        let context = &mut self.context;
+        let channels = context.input_parameters.channels as usize;
+        if self.input_device == self.output_device {
+            enable_io(&context.unit, true, true)?;
+            set_device(&context.unit, &self.output_device)?;
+            set_input_format(&context.unit, &context.input_parameters)?;
+            let mut buffers = CaptureBuffers::new();
+            buffers.allocate(&context.unit, channels)?;
+            context.render_state.get_mut().input = DuplexInput::Unit(buffers);
+        } else {
+            enable_io(&context.unit, false, true)?;
+            set_device(&context.unit, &self.output_device)?;
+            let unit = AudioUnit::new_hal()?;
+            enable_io(&unit, true, false)?;
+            set_device(&unit, &self.input_device)?;
+            set_input_format(&unit, &context.input_parameters)?;
+            // Keep the frames of the larger callback of the two units in the
+            // queue.
+            let output_frames = get_maximum_frames(&context.unit)?;
+            let target = get_maximum_frames(&unit)?.max(output_frames);
+            let (fifo, writer, reader) =
+                Fifo::create(channels, FIFO_CAPACITY_IN_CALLBACKS * target, target);
+            let capture = CaptureContext::new(unit, channels, writer)?;
+            context.render_state.get_mut().input = DuplexInput::Fifo {
+                reader,
+                buffers: vec![vec![T::SILENCE; output_frames]; channels],
+                slices: SliceTable::new(channels),
+            };
+            self.capture = Some(capture);
+            self.fifo = Some(fifo);
+        }
+        set_output_format(&context.unit, &context.output_parameters)?;
+        context.set_callback()?;
+        context.unit.initialize()?;
+        Ok(())
+    }
+}
+
+impl<T: Sample> DuplexContext<T> {
+    fn set_callback(&self) -> Result<(), Error> {
+        let callback_struct = sys::AURenderCallbackStruct {
+            inputProc: Some(Self::audio_unit_callback),
+            inputProcRefCon: self as *const Self as *mut c_void,
+        };
+        self.unit.set_property(
+            sys::kAudioUnitProperty_SetRenderCallback,
+            sys::kAudioUnitScope_Input,
+            Element::Output,
+            &callback_struct,
+        )?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        io_action_flags: *mut sys::AudioUnitRenderActionFlags,
+        in_time_stamp: *const sys::AudioTimeStamp,
+        in_number_of_frames: sys::UInt32,
+        io_data: *mut sys::AudioBufferList,
+    ) -> sys::OSStatus {
+        let frames = in_number_of_frames as usize;
+        let buffers = unsafe {
+            let ptr = (*io_data).mBuffers.as_mut_ptr();
+            let len = (*io_data).mNumberBuffers as usize;
+            slice::from_raw_parts_mut(ptr, len)
+        };
+        let byte_size = (frames * size_of::<T>()) as u32;
+        let valid = buffers.len() as u32 == self.output_parameters.channels
+            && buffers
+                .iter()
+                .all(|buffer| buffer.mNumberChannels == 1 && buffer.mDataByteSize == byte_size);
+        if !valid {
+            return sys::kAudioUnitErr_FormatNotSupported;
+        }
+
+        let timing = &get_timing(in_time_stamp);
+        // The output callback is the only one using the render state.
+        let render_state = unsafe { &mut *self.render_state.get() };
+        let DuplexRenderState {
+            ref mut callback,
+            ref mut input,
+            ref mut output,
+        } = *render_state;
+        let pointers = buffers.iter().map(|buffer| buffer.mData as *mut T);
+        unsafe {
+            output.with_slices(pointers, frames, |output| match *input {
+                DuplexInput::Unit(ref mut buffers) => match buffers.render(
+                    &self.unit,
+                    io_action_flags,
+                    in_time_stamp,
+                    in_number_of_frames,
+                    move |input| callback(input, output, timing),
+                ) {
+                    Ok(()) => sys::noErr as sys::OSStatus,
+                    Err(status) => status,
+                },
+                DuplexInput::Fifo {
+                    ref mut reader,
+                    ref mut buffers,
+                    ref mut slices,
+                } => {
+                    if buffers.iter().any(|buffer| buffer.len() < frames) {
+                        return sys::kAudioUnitErr_TooManyFramesToProcess;
+                    }
+                    let pointers = buffers.iter_mut().map(|buffer| buffer.as_mut_ptr());
+                    slices.with_slices(pointers, frames, |data| reader.pop(data));
+                    let pointers = buffers.iter_mut().map(|buffer| buffer.as_mut_ptr());
+                    slices.with_read_only_slices(pointers, frames, move |input| {
+                        callback(input, output, timing)
+                    });
+                    sys::noErr as sys::OSStatus
+                }
+            })
+        }
+    }
+
+    // The *static* callback function that will be registered into
+    // `AURenderCallbackStruct` and called by underlying `AudioUnit`
+    // framework directly.
+    extern "C" fn audio_unit_callback(
+        in_ref_con: *mut c_void,
+        io_action_flags: *mut sys::AudioUnitRenderActionFlags,
+        in_time_stamp: *const sys::AudioTimeStamp,
+        _in_bus_number: sys::UInt32,
+        in_number_of_frames: sys::UInt32,
+        io_data: *mut sys::AudioBufferList,
+    ) -> sys::OSStatus {
+        let context = in_ref_con as *const Self;
+        unsafe { (*context).render(io_action_flags, in_time_stamp, in_number_of_frames, io_data) }
+    }
+}
+
+// Tear down both units even if one of them fails. Don't panic here since the
+// units fail to stop once their devices are unplugged, or the stream may be
+// dropped while unwinding.
+impl<T: Sample> Drop for DuplexStream<T> {
+    fn drop(&mut self) {
+        let _ = self.context.unit.stop();
+        if let Some(ref capture) = self.capture {
+            let _ = capture.unit.stop();
+            let _ = capture.unit.uninitialize();
+        }
+        let _ = self.context.unit.uninitialize();
+    }
+}
+
+// CaptureContext
+// ============================================================================
+// The context of the input callback on a different device from the output
+// one. The unit is shared with the thread owning the stream. The rest is in
+// `capture_state`, only touched by the input callback once the callback is
+// set.
+struct CaptureContext<T> {
+    unit: AudioUnit,
+    capture_state: UnsafeCell<CaptureState<T>>,
+}
+
+struct CaptureState<T> {
+    buffers: CaptureBuffers<T>,
+    writer: FifoWriter<T>,
+}
+
+impl<T: Sample> CaptureContext<T> {
+    // Capture the data by the given unit set up for the input device. The
+    // context is boxed so its address is fixed for the callback.
+    fn new(unit: AudioUnit, channels: usize, writer: FifoWriter<T>) -> Result<Box<Self>, Error> {
+        let mut buffers = CaptureBuffers::new();
+        buffers.allocate(&unit, channels)?;
+        let capture = Box::new(CaptureContext {
+            unit,
+            capture_state: UnsafeCell::new(CaptureState { buffers, writer }),
+        });
+        let data = &*capture as *const Self as *mut c_void;
+        set_input_callback(&capture.unit, Some(Self::audio_unit_input_callback), data)?;
+        capture.unit.initialize()?;
+        Ok(capture)
+    }
+
+    fn capture(
+        &self,
+        io_action_flags: *mut sys::AudioUnitRenderActionFlags,
+        in_time_stamp: *const sys::AudioTimeStamp,
+        in_number_of_frames: sys::UInt32,
+    ) -> sys::OSStatus {
+        // The input callback is the only one using the capture state.
+        let capture_state = unsafe { &mut *self.capture_state.get() };
+        let CaptureState {
+            ref mut buffers,
+            ref mut writer,
+        } = *capture_state;
+        match buffers.render(
+            &self.unit,
+            io_action_flags,
+            in_time_stamp,
+            in_number_of_frames,
+            |input| writer.push(input),
+        ) {
+            Ok(()) => sys::noErr as sys::OSStatus,
+            Err(status) => status,
+        }
+    }
+
+    extern "C" fn audio_unit_input_callback(
+        in_ref_con: *mut c_void,
+        io_action_flags: *mut sys::AudioUnitRenderActionFlags,
+        in_time_stamp: *const sys::AudioTimeStamp,
+        _in_bus_number: sys::UInt32,
+        in_number_of_frames: sys::UInt32,
+        _io_data: *mut sys::AudioBufferList,
+    ) -> sys::OSStatus {
+        let capture = in_ref_con as *const Self;
+        unsafe { (*capture).capture(io_action_flags, in_time_stamp, in_number_of_frames) }
+    }
+}
@@ -0,0 +1,183 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::Sample;
+
+// Fifo
+// ============================================================================
+// The lock-free single-producer single-consumer queue passing the data from
+// the input callback to the output callback when they run on different
+// devices. All the memory is allocated when the queue is created, so nothing
+// is allocated or locked on the audio threads. The only `FifoWriter` is used
+// by the input callback and the only `FifoReader` by the output callback.
+//
+// The clocks of the two devices drift apart over time, and the callbacks of
+// the two devices don't come at the same pace. The reader keeps about
+// `target` frames in the queue to absorb the jitter:
+// - The reader plays silence until the queue is filled to the target level
+//   plus the frames it reads.
+// - If the output runs faster, the queue runs dry. The missing frames are
+//   filled with silence and counted as an underrun, and the reader waits for
+//   the queue to be filled to the target level again.
+// - If the input runs faster, the queue grows. The reader drops the oldest
+//   frames beyond twice the target level, and the writer drops the frames
+//   that don't fit in the queue. Both are counted as overruns.
+pub struct Fifo<T> {
+    // The frames of all the channels, interleaved.
+    samples: Box<[UnsafeCell<T>]>,
+    channels: usize,
+    capacity: usize, // In frames.
+    target: usize,   // In frames.
+    // The number of the frames written and read so far. Only the writer moves
+    // `written` and only the reader moves `read`.
+    written: AtomicUsize,
+    read: AtomicUsize,
+    overruns: AtomicUsize,
+    underruns: AtomicUsize,
+}
+
+// The writer only writes the frames not yet written, and the reader only reads
+// the frames already written, so a sample is never touched by both at the
+// same time.
+unsafe impl<T: Send> Sync for Fifo<T> {}
+
+impl<T: Sample> Fifo<T> {
+    // Create a queue holding `capacity` frames of the channels, with its only
+    // writer and reader. The returned queue is used to get the counts of the
+    // overruns and underruns.
+    pub fn create(
+        channels: usize,
+        capacity: usize,
+        target: usize,
+    ) -> (Arc<Fifo<T>>, FifoWriter<T>, FifoReader<T>) {
+        let capacity = capacity.max(1);
+        let fifo = Arc::new(Fifo {
+            samples: (0..channels * capacity)
+                .map(|_| UnsafeCell::new(T::SILENCE))
+                .collect(),
+            channels,
+            capacity,
+            target,
+            written: AtomicUsize::new(0),
+            read: AtomicUsize::new(0),
+            overruns: AtomicUsize::new(0),
+            underruns: AtomicUsize::new(0),
+        });
+        let writer = FifoWriter { fifo: fifo.clone() };
+        let reader = FifoReader {
+            fifo: fifo.clone(),
+            filling: true,
+        };
+        (fifo, writer, reader)
+    }
+
+    // The number of the times the data is dropped since the input runs
+    // faster than the output.
+    pub fn get_overruns(&self) -> usize {
+        self.overruns.load(Ordering::SeqCst)
+    }
+
+    // The number of the times the data is filled with silence since the
+    // output runs faster than the input.
+    pub fn get_underruns(&self) -> usize {
+        self.underruns.load(Ordering::SeqCst)
+    }
+
+    fn get_sample(&self, frame: usize, channel: usize) -> *mut T {
+        self.samples[(frame % self.capacity) * self.channels + channel].get()
+    }
+}
+
+// FifoWriter
+// ============================================================================
+pub struct FifoWriter<T> {
+    fifo: Arc<Fifo<T>>,
+}
+
+impl<T: Sample> FifoWriter<T> {
+    // Append the frames of the given channels. The channels missing in the
+    // data are filled with silence, and the channels beyond the queue are
+    // ignored.
+    pub fn push(&mut self, data: &[&[T]]) {
+        let fifo = &*self.fifo;
+        let frames = data.iter().map(|channel| channel.len()).min().unwrap_or(0);
+        let written = fifo.written.load(Ordering::SeqCst);
+        let buffered = written.wrapping_sub(fifo.read.load(Ordering::SeqCst));
+        let count = frames.min(fifo.capacity - buffered);
+        if count < frames {
+            fifo.overruns.fetch_add(1, Ordering::SeqCst);
+        }
+        for frame in 0..count {
+            for channel in 0..fifo.channels {
+                let sample = data.get(channel).map_or(T::SILENCE, |data| data[frame]);
+                unsafe {
+                    *fifo.get_sample(written.wrapping_add(frame), channel) = sample;
+                }
+            }
+        }
+        fifo.written
+            .store(written.wrapping_add(count), Ordering::SeqCst);
+    }
+}
+
+// FifoReader
+// ============================================================================
+pub struct FifoReader<T> {
+    fifo: Arc<Fifo<T>>,
+    // Waiting for the queue to be filled to the target level.
+    filling: bool,
+}
+
+impl<T: Sample> FifoReader<T> {
+    // Fill the given channels with the oldest frames in the queue. The
+    // channels beyond the queue are filled with silence, and the channels
+    // missing in the data are skipped.
+    pub fn pop(&mut self, data: &mut [&mut [T]]) {
+        let fifo = &*self.fifo;
+        let frames = data.iter().map(|channel| channel.len()).min().unwrap_or(0);
+        let mut read = fifo.read.load(Ordering::SeqCst);
+        let mut buffered = fifo.written.load(Ordering::SeqCst).wrapping_sub(read);
+        if self.filling {
+            if buffered < fifo.target + frames {
+                fill_silence(data);
+                return;
+            }
+            self.filling = false;
+        }
+        if buffered > frames + 2 * fifo.target {
+            let dropped = buffered - (frames + fifo.target);
+            read = read.wrapping_add(dropped);
+            buffered -= dropped;
+            fifo.overruns.fetch_add(1, Ordering::SeqCst);
+        }
+        let count = frames.min(buffered);
+        for (channel, output) in data.iter_mut().enumerate() {
+            for (frame, sample) in output.iter_mut().enumerate() {
+                *sample = if channel < fifo.channels && frame < count {
+                    unsafe { *fifo.get_sample(read.wrapping_add(frame), channel) }
+                } else {
+                    T::SILENCE
+                };
+            }
+        }
+        fifo.read.store(read.wrapping_add(count), Ordering::SeqCst);
+        if count < frames {
+            fifo.underruns.fetch_add(1, Ordering::SeqCst);
+            self.filling = true;
+        }
+    }
+}
+
+fn fill_silence<T: Sample>(data: &mut [&mut [T]]) {
+    for output in data.iter_mut() {
+        for sample in output.iter_mut() {
+            *sample = T::SILENCE;
+        }
+    }
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
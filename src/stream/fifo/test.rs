@@ -0,0 +1,142 @@
+use super::*;
+use std::thread;
+
+// Tests for Public Functions
+// ============================================================================
+
+// FifoWriter::push
+// FifoReader::pop
+// ------------------------------------
+#[test]
+fn test_push_and_pop() {
+    let (fifo, mut writer, mut reader) = Fifo::<i16>::create(2, 8, 2);
+    writer.push(&[&[1, 2, 3, 4], &[5, 6, 7, 8]]);
+
+    let mut left = [0; 2];
+    let mut right = [0; 2];
+    reader.pop(&mut [&mut left, &mut right]);
+    assert_eq!(left, [1, 2]);
+    assert_eq!(right, [5, 6]);
+    reader.pop(&mut [&mut left, &mut right]);
+    assert_eq!(left, [3, 4]);
+    assert_eq!(right, [7, 8]);
+    assert_eq!(fifo.get_overruns(), 0);
+    assert_eq!(fifo.get_underruns(), 0);
+}
+
+#[test]
+fn test_pop_until_target_level() {
+    let (fifo, mut writer, mut reader) = Fifo::<f32>::create(1, 16, 4);
+    writer.push(&[&[1.0, 2.0, 3.0, 4.0]]);
+
+    // Wait for the target level plus the frames to read.
+    let mut data = [9.0; 2];
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [0.0; 2]);
+
+    writer.push(&[&[5.0, 6.0]]);
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [1.0, 2.0]);
+    assert_eq!(fifo.get_underruns(), 0);
+}
+
+#[test]
+fn test_pop_with_underrun() {
+    // The output runs faster than the input.
+    let (fifo, mut writer, mut reader) = Fifo::<u8>::create(1, 16, 2);
+    writer.push(&[&[1, 2, 3, 4]]);
+
+    let mut data = [0; 2];
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [1, 2]);
+
+    // The missing frames are filled with the silence of the sample type.
+    let mut data = [0; 4];
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [3, 4, 128, 128]);
+    assert_eq!(fifo.get_underruns(), 1);
+
+    // Wait for the target level again.
+    writer.push(&[&[5, 6, 7, 8]]);
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [128; 4]);
+    assert_eq!(fifo.get_underruns(), 1);
+    writer.push(&[&[9, 10]]);
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [5, 6, 7, 8]);
+}
+
+#[test]
+fn test_push_over_capacity() {
+    // The input runs faster than the output.
+    let (fifo, mut writer, mut reader) = Fifo::<i32>::create(1, 4, 0);
+    writer.push(&[&[1, 2, 3]]);
+    writer.push(&[&[4, 5, 6]]);
+    assert_eq!(fifo.get_overruns(), 1);
+
+    let mut data = [0; 4];
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [1, 2, 3, 4]);
+}
+
+#[test]
+fn test_pop_over_target_level() {
+    let (fifo, mut writer, mut reader) = Fifo::<i32>::create(1, 16, 2);
+    writer.push(&[&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]]);
+
+    // The oldest frames are dropped to keep the target level after reading.
+    let mut data = [0; 2];
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [7, 8]);
+    assert_eq!(fifo.get_overruns(), 1);
+    reader.pop(&mut [&mut data]);
+    assert_eq!(data, [9, 10]);
+}
+
+#[test]
+fn test_push_and_pop_with_other_channels() {
+    let (_fifo, mut writer, mut reader) = Fifo::<f32>::create(2, 8, 0);
+    // The missing channel is filled with silence.
+    writer.push(&[&[1.0, 2.0]]);
+
+    // The channel beyond the queue is filled with silence.
+    let mut data = [[9.0_f32; 2]; 3];
+    {
+        let (first, rest) = data.split_at_mut(1);
+        let (second, third) = rest.split_at_mut(1);
+        reader.pop(&mut [&mut first[0], &mut second[0], &mut third[0]]);
+    }
+    assert_eq!(data, [[1.0, 2.0], [0.0, 0.0], [0.0, 0.0]]);
+}
+
+#[test]
+fn test_push_and_pop_on_threads() {
+    const FRAMES: i32 = 10_000;
+    let (_fifo, mut writer, mut reader) = Fifo::<i32>::create(1, 64, 8);
+    let writing = thread::spawn(move || {
+        for start in (1..=FRAMES).step_by(16) {
+            let data: Vec<i32> = (start..(start + 16).min(FRAMES + 1)).collect();
+            writer.push(&[&data]);
+            thread::yield_now();
+        }
+    });
+
+    // The frames come in order, with the silence or the gaps of the xruns.
+    let mut last = 0;
+    let mut data = [0; 16];
+    loop {
+        let done = writing.is_finished();
+        reader.pop(&mut [&mut data]);
+        for &sample in data.iter().filter(|&&sample| sample != 0) {
+            assert!(sample > last);
+            last = sample;
+        }
+        // The frames below the target level are left in the queue.
+        if last == FRAMES || (done && data == [0; 16]) {
+            break;
+        }
+        thread::yield_now();
+    }
+    writing.join().unwrap();
+    assert!(last > 0);
+}
@@ -0,0 +1,233 @@
+extern crate coreaudio_sys as sys;
+
+use std::mem::size_of;
+use std::os::raw::c_void;
+use std::slice;
+
+use super::audio_unit::{AudioUnit, Element};
+use super::auhal::{
+    enable_io, get_maximum_frames, set_device, set_input_callback, set_input_format,
+};
+use super::slice_table::SliceTable;
+use super::timing::get_timing;
+use super::{check_input_rate, check_scope, Error, Parameters, Sample, Timing};
+use utils::{get_default_device, AudioObject, Scope};
+
+pub type InputCallbackArgs<'a, T> = &'a [&'a [T]];
+// The `Timing` tells when the data in the buffers was captured.
+type InputCallback<T> = Box<dyn FnMut(InputCallbackArgs<T>, &Timing) + Send>;
+
+// The captured data is pulled by `AudioUnitRender` in the input callback of
+// the AUHAL unit, so the unit lives in the heap-allocated context along with
+// the callback. See the `Stream` for why the context is boxed.
+pub struct InputStream<T: Sample> {
+    device: AudioObject,
+    context: Box<InputContext<T>>,
+}
+
+struct InputContext<T> {
+    callback: InputCallback<T>,
+    parameters: Parameters,
+    unit: AudioUnit,
+    buffers: CaptureBuffers<T>,
+}
+
+// Learn how AUHAL captures the data from below link:
+// https://developer.apple.com/library/archive/technotes/tn2091/_index.html
+impl<T: Sample> InputStream<T> {
+    // Capture from the default input device.
+    pub fn new<F>(channels: u32, rate: f64, callback: F) -> Result<Self, Error>
+    where
+        F: FnMut(InputCallbackArgs<T>, &Timing) + Send + 'static,
+    {
+        let device = get_default_device(&Scope::Input)?;
+        Self::with_device(&device, channels, rate, callback)
+    }
+
+    // Return `Error::UnsupportedRate` if the rate isn't the nominal rate of
+    // the device.
+    pub fn with_device<F>(
+        device: &AudioObject,
+        channels: u32,
+        rate: f64,
+        callback: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(InputCallbackArgs<T>, &Timing) + Send + 'static,
+    {
+        check_scope(device, &Scope::Input)?;
+        check_input_rate(device, rate)?;
+        let parameters = Parameters::new(channels, T::FORMAT, rate);
+        let unit = AudioUnit::new_hal()?;
+        let mut stm = InputStream {
+            device: device.clone(),
+            context: Box::new(InputContext {
+                callback: Box::new(callback),
+                parameters,
+                unit,
+                buffers: CaptureBuffers::new(),
+            }),
+        };
+        stm.init()?;
+        Ok(stm)
+    }
+
+    pub fn start(&self) -> Result<(), Error> {
+        self.context.unit.start()?;
+        Ok(())
+    }
+
+    pub fn stop(&self) -> Result<(), Error> {
+        self.context.unit.stop()?;
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<(), Error> {
+        let context = &mut self.context;
+        enable_io(&context.unit, true, false)?;
+        set_device(&context.unit, &self.device)?;
+        set_input_format(&context.unit, &context.parameters)?;
+        context
+            .buffers
+            .allocate(&context.unit, context.parameters.channels as usize)?;
+        context.set_callback()?;
+        context.unit.initialize()?;
+        Ok(())
+    }
+}
+
+impl<T> InputContext<T> {
+    fn set_callback(&mut self) -> Result<(), Error> {
+        let data = self as *mut Self as *mut c_void;
+        set_input_callback(&self.unit, Some(Self::audio_unit_input_callback), data)
+    }
+
+    fn capture(
+        &mut self,
+        io_action_flags: *mut sys::AudioUnitRenderActionFlags,
+        in_time_stamp: *const sys::AudioTimeStamp,
+        in_number_of_frames: sys::UInt32,
+    ) -> sys::OSStatus {
+        let callback = &mut self.callback;
+        let timing = get_timing(in_time_stamp);
+        match self.buffers.render(
+            &self.unit,
+            io_action_flags,
+            in_time_stamp,
+            in_number_of_frames,
+            |data| callback(data, &timing),
+        ) {
+            Ok(()) => sys::noErr as sys::OSStatus,
+            Err(status) => status,
+        }
+    }
+
+    // The *static* callback function that will be registered as the input
+    // callback and called by underlying `AudioUnit` framework directly. The
+    // `io_data` is always null for the input callback.
+    extern "C" fn audio_unit_input_callback(
+        in_ref_con: *mut c_void,
+        io_action_flags: *mut sys::AudioUnitRenderActionFlags,
+        in_time_stamp: *const sys::AudioTimeStamp,
+        _in_bus_number: sys::UInt32,
+        in_number_of_frames: sys::UInt32,
+        _io_data: *mut sys::AudioBufferList,
+    ) -> sys::OSStatus {
+        let context = in_ref_con as *mut Self;
+        unsafe { (*context).capture(io_action_flags, in_time_stamp, in_number_of_frames) }
+    }
+}
+
+// Don't panic here since the unit fails to stop once the device is unplugged,
+// or the stream may be dropped while unwinding.
+impl<T: Sample> Drop for InputStream<T> {
+    fn drop(&mut self) {
+        let _ = self.stop();
+        let _ = self.context.unit.uninitialize();
+    }
+}
+
+// CaptureBuffers
+// ============================================================================
+// The buffers to store the data pulled from the input element of an AUHAL
+// unit. They are allocated before the unit starts so no allocation happens in
+// the audio callbacks.
+pub struct CaptureBuffers<T> {
+    // The buffer of each channel. Only its capacity is used.
+    channel_buffers: Vec<Vec<T>>,
+    // The memory of a `AudioBufferList` with one `AudioBuffer` per channel.
+    // Using `u64` to align the `AudioBuffer`s.
+    buffer_list: Vec<u64>,
+    // The table of the captured data passed to the callbacks.
+    slices: SliceTable<T>,
+}
+
+impl<T> CaptureBuffers<T> {
+    pub fn new() -> Self {
+        CaptureBuffers {
+            channel_buffers: Vec::new(),
+            buffer_list: Vec::new(),
+            slices: SliceTable::new(0),
+        }
+    }
+
+    // Allocate the buffers for the maximum frames the unit may process at a
+    // time.
+    pub fn allocate(&mut self, unit: &AudioUnit, channels: usize) -> Result<(), Error> {
+        let frames = get_maximum_frames(unit)?;
+        self.channel_buffers = (0..channels).map(|_| Vec::with_capacity(frames)).collect();
+        // The `AudioBufferList` already contains one `AudioBuffer`.
+        let list_size = size_of::<sys::AudioBufferList>()
+            + size_of::<sys::AudioBuffer>() * channels.saturating_sub(1);
+        // Allocate one more word in case the size isn't a multiple of `u64`.
+        let words = list_size / size_of::<u64>() + 1;
+        self.buffer_list = vec![0; words];
+        self.slices = SliceTable::new(channels);
+        Ok(())
+    }
+
+    // Pull the captured data from the input element of the unit, and call `f`
+    // with the data of each channel.
+    pub fn render<F, R>(
+        &mut self,
+        unit: &AudioUnit,
+        io_action_flags: *mut sys::AudioUnitRenderActionFlags,
+        in_time_stamp: *const sys::AudioTimeStamp,
+        in_number_of_frames: sys::UInt32,
+        f: F,
+    ) -> Result<R, sys::OSStatus>
+    where
+        F: for<'a> FnOnce(InputCallbackArgs<'a, T>) -> R,
+    {
+        let frames = in_number_of_frames as usize;
+        if self.channel_buffers.iter().any(|b| b.capacity() < frames) {
+            return Err(sys::kAudioUnitErr_TooManyFramesToProcess);
+        }
+
+        let list = self.buffer_list.as_mut_ptr() as *mut sys::AudioBufferList;
+        let buffers = unsafe {
+            (*list).mNumberBuffers = self.channel_buffers.len() as u32;
+            let ptr = (*list).mBuffers.as_mut_ptr();
+            slice::from_raw_parts_mut(ptr, self.channel_buffers.len())
+        };
+        for (buffer, channel_buffer) in buffers.iter_mut().zip(self.channel_buffers.iter_mut()) {
+            buffer.mNumberChannels = 1;
+            buffer.mDataByteSize = (frames * size_of::<T>()) as u32;
+            buffer.mData = channel_buffer.as_mut_ptr() as *mut c_void;
+        }
+
+        let status = unit.render(
+            io_action_flags,
+            in_time_stamp,
+            Element::Input,
+            in_number_of_frames,
+            list,
+        );
+        if status != sys::noErr as sys::OSStatus {
+            return Err(status);
+        }
+
+        let pointers = buffers.iter().map(|buffer| buffer.mData as *mut T);
+        Ok(unsafe { self.slices.with_read_only_slices(pointers, frames, f) })
+    }
+}
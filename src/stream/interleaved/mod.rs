@@ -0,0 +1,74 @@
+use std::ops::{Deref, DerefMut};
+use std::slice::{Chunks, ChunksMut};
+
+// A wrapper around the interleaved data of all the channels, which is laid
+// out frame by frame as `[L0, R0, L1, R1, ...]` for a stereo stream. It can
+// be used as a plain `[T]` or accessed by frames and channels.
+pub struct InterleavedBuffer<'a, T: 'a> {
+    data: &'a mut [T],
+    channels: usize,
+}
+
+impl<'a, T> InterleavedBuffer<'a, T> {
+    pub fn new(data: &'a mut [T], channels: usize) -> Self {
+        assert!(channels > 0);
+        assert_eq!(data.len() % channels, 0);
+        InterleavedBuffer { data, channels }
+    }
+
+    pub fn get_channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn get_frames(&self) -> usize {
+        self.data.len() / self.channels
+    }
+
+    // Return the samples of all the channels in the given frame.
+    pub fn get_frame(&self, frame: usize) -> &[T] {
+        let start = frame * self.channels;
+        &self.data[start..start + self.channels]
+    }
+
+    pub fn get_frame_mut(&mut self, frame: usize) -> &mut [T] {
+        let start = frame * self.channels;
+        &mut self.data[start..start + self.channels]
+    }
+
+    pub fn get_sample(&self, frame: usize, channel: usize) -> &T {
+        assert!(channel < self.channels);
+        &self.data[frame * self.channels + channel]
+    }
+
+    pub fn get_sample_mut(&mut self, frame: usize, channel: usize) -> &mut T {
+        assert!(channel < self.channels);
+        &mut self.data[frame * self.channels + channel]
+    }
+
+    pub fn frames(&self) -> Chunks<'_, T> {
+        self.data.chunks(self.channels)
+    }
+
+    pub fn frames_mut(&mut self) -> ChunksMut<'_, T> {
+        self.data.chunks_mut(self.channels)
+    }
+}
+
+impl<'a, T> Deref for InterleavedBuffer<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.data
+    }
+}
+
+impl<'a, T> DerefMut for InterleavedBuffer<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.data
+    }
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
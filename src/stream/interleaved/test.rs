@@ -0,0 +1,85 @@
+use super::*;
+
+// Tests for Public Functions
+// ============================================================================
+
+// InterleavedBuffer::new
+// ------------------------------------
+#[test]
+#[should_panic]
+fn test_new_with_partial_frame() {
+    let mut data = [0.0_f32; 5];
+    InterleavedBuffer::new(&mut data, 2);
+}
+
+#[test]
+#[should_panic]
+fn test_new_without_channels() {
+    let mut data = [0.0_f32; 4];
+    InterleavedBuffer::new(&mut data, 0);
+}
+
+// InterleavedBuffer::get_channels
+// InterleavedBuffer::get_frames
+// ------------------------------------
+#[test]
+fn test_get_channels_and_frames() {
+    let mut data = [0_i16; 12];
+    let buffer = InterleavedBuffer::new(&mut data, 3);
+    assert_eq!(buffer.get_channels(), 3);
+    assert_eq!(buffer.get_frames(), 4);
+    assert_eq!(buffer.len(), 12);
+}
+
+// InterleavedBuffer::get_frame
+// InterleavedBuffer::get_frame_mut
+// ------------------------------------
+#[test]
+fn test_get_frame() {
+    let mut data = [0, 1, 2, 3, 4, 5];
+    let mut buffer = InterleavedBuffer::new(&mut data, 2);
+    assert_eq!(buffer.get_frame(1), &[2, 3]);
+    buffer.get_frame_mut(2)[0] = 40;
+    assert_eq!(buffer.get_frame(2), &[40, 5]);
+}
+
+// InterleavedBuffer::get_sample
+// InterleavedBuffer::get_sample_mut
+// ------------------------------------
+#[test]
+fn test_get_sample() {
+    let mut data = [0, 1, 2, 3, 4, 5];
+    {
+        let mut buffer = InterleavedBuffer::new(&mut data, 2);
+        assert_eq!(*buffer.get_sample(2, 1), 5);
+        *buffer.get_sample_mut(0, 1) = 10;
+    }
+    assert_eq!(data, [0, 10, 2, 3, 4, 5]);
+}
+
+#[test]
+#[should_panic]
+fn test_get_sample_with_invalid_channel() {
+    let mut data = [0, 1, 2, 3];
+    let buffer = InterleavedBuffer::new(&mut data, 2);
+    buffer.get_sample(0, 2);
+}
+
+// InterleavedBuffer::frames
+// InterleavedBuffer::frames_mut
+// ------------------------------------
+#[test]
+fn test_frames() {
+    let mut data = [0, 0, 0, 0, 0, 0];
+    {
+        let mut buffer = InterleavedBuffer::new(&mut data, 2);
+        for (i, frame) in buffer.frames_mut().enumerate() {
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                *sample = i * 10 + channel;
+            }
+        }
+        let frames: Vec<&[usize]> = buffer.frames().collect();
+        assert_eq!(frames, vec![&[0, 1][..], &[10, 11][..], &[20, 21][..]]);
+    }
+    assert_eq!(data, [0, 1, 10, 11, 20, 21]);
+}
@@ -1,17 +1,63 @@
 extern crate coreaudio_sys as sys;
 
+use std::cell::UnsafeCell;
 use std::marker::PhantomData;
 use std::mem::size_of;
 use std::os::raw::c_void;
 use std::slice;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle, Thread};
 
 mod audio_unit;
+mod auhal;
+mod drain;
+mod duplex_stream;
+mod fifo;
+mod input_stream;
+mod interleaved;
+mod negotiation;
+mod position;
+mod sample;
+mod slice_table;
+mod state;
+mod timing;
+mod volume;
 
 use self::audio_unit::{AudioUnit, Element};
+use self::auhal::{enable_io, set_device};
+use self::drain::Drain;
+pub use self::duplex_stream::DuplexStream;
+pub use self::input_stream::{InputCallbackArgs, InputStream};
+pub use self::interleaved::InterleavedBuffer;
+pub use self::negotiation::{
+    negotiate, negotiate_with_capabilities, DeviceCapabilities, Negotiation,
+};
+use self::position::{Position, PositionTracker};
+pub use self::sample::{BigEndian, I24In32, Sample, I24};
+use self::slice_table::SliceTable;
+pub use self::state::State;
+use self::state::{AtomicState, PendingStates};
+use self::timing::get_timing;
+pub use self::timing::{get_current_host_time, Timing};
+use self::volume::{Gain, Volume};
+use utils::{self, get_default_device, AudioObject, PropertyListener, Scope};
 
 #[derive(Debug)]
 pub enum Error {
     AudioUnit(audio_unit::Error),
+    // The operation isn't allowed in the current state of the stream.
+    InvalidState(State),
+    // The stream plays on the given device, so it never switches to another
+    // device.
+    FixedDevice,
+    // The device of the stream is removed. The stream can't be used again.
+    DeviceLost,
+    // The rate differs from the nominal rate of the input device, which is
+    // given here.
+    UnsupportedRate(f64),
+    Utils(utils::Error),
+    WrongScope,
 }
 
 // To convert a audio_unit::Error to a Error.
@@ -21,58 +67,143 @@ impl From<audio_unit::Error> for Error {
     }
 }
 
-// TODO: Use native type to infer format directly.
+// To convert a utils::Error to a Error.
+impl From<utils::Error> for Error {
+    fn from(e: utils::Error) -> Self {
+        Error::Utils(e)
+    }
+}
+
+// The format of a stream is derived from the `Sample` type of its callback.
+// The sample type of each format is noted below.
+#[derive(Clone, Debug, PartialEq)]
 pub enum Format {
-    S16LE, // PCM signed 16-bit little-endian.
-    F32LE, // PCM 32-bit floating-point little-endian.
+    U8,       // PCM unsigned 8-bit. (u8)
+    S16LE,    // PCM signed 16-bit little-endian. (i16)
+    S16BE,    // PCM signed 16-bit big-endian. (BigEndian<i16>)
+    S24LE,    // PCM signed 24-bit little-endian packed in 3 bytes. (I24)
+    S24BE,    // PCM signed 24-bit big-endian packed in 3 bytes. (BigEndian<I24>)
+    S24_32LE, // PCM signed 24-bit little-endian in low 3 bytes of 4 bytes. (I24In32)
+    S24_32BE, // PCM signed 24-bit big-endian in low 3 bytes of 4 bytes. (BigEndian<I24In32>)
+    S32LE,    // PCM signed 32-bit little-endian. (i32)
+    S32BE,    // PCM signed 32-bit big-endian. (BigEndian<i32>)
+    F32LE,    // PCM 32-bit floating-point little-endian. (f32)
+    F32BE,    // PCM 32-bit floating-point big-endian. (BigEndian<f32>)
+    F64LE,    // PCM 64-bit floating-point little-endian. (f64)
+    F64BE,    // PCM 64-bit floating-point big-endian. (BigEndian<f64>)
 }
 
 impl Format {
     fn byte_size(&self) -> usize {
         match self {
-            Format::S16LE => size_of::<i16>(),
-            Format::F32LE => size_of::<f32>(),
+            Format::U8 => size_of::<u8>(),
+            Format::S16LE | Format::S16BE => size_of::<i16>(),
+            Format::S24LE | Format::S24BE => size_of::<I24>(),
+            Format::S24_32LE | Format::S24_32BE | Format::S32LE | Format::S32BE => size_of::<i32>(),
+            Format::F32LE | Format::F32BE => size_of::<f32>(),
+            Format::F64LE | Format::F64BE => size_of::<f64>(),
+        }
+    }
+
+    fn bits_per_channel(&self) -> u32 {
+        match self {
+            // Only the low 24 bits of the 32-bit containers are used.
+            Format::S24_32LE | Format::S24_32BE => 24,
+            _ => (self.byte_size() * 8) as u32,
         }
     }
 
+    fn is_big_endian(&self) -> bool {
+        matches!(
+            self,
+            Format::S16BE
+                | Format::S24BE
+                | Format::S24_32BE
+                | Format::S32BE
+                | Format::F32BE
+                | Format::F64BE
+        )
+    }
+
     fn to_format_flags(&self) -> sys::AudioFormatFlags {
-        let flags = match self {
-            Format::S16LE => sys::kAudioFormatFlagIsSignedInteger,
-            Format::F32LE => sys::kAudioFormatFlagIsFloat,
+        let mut flags = match self {
+            // The unsigned integer has no flag.
+            Format::U8 => 0,
+            Format::F32LE | Format::F32BE | Format::F64LE | Format::F64BE => {
+                sys::kAudioFormatFlagIsFloat
+            }
+            _ => sys::kAudioFormatFlagIsSignedInteger,
         };
-        flags | sys::kLinearPCMFormatFlagIsPacked | sys::kLinearPCMFormatFlagIsNonInterleaved
+        if self.is_big_endian() {
+            flags |= sys::kAudioFormatFlagIsBigEndian;
+        }
+        // The samples in 32-bit containers don't use all the bits, so they
+        // are not packed. They are aligned low since there is no
+        // `kLinearPCMFormatFlagIsAlignedHigh`.
+        if self.bits_per_channel() as usize == self.byte_size() * 8 {
+            flags |= sys::kLinearPCMFormatFlagIsPacked;
+        }
+        flags
+    }
+}
+
+// How the data of the channels is put in the buffers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Layout {
+    Interleaved,    // All channels in one buffer, frame by frame.
+    NonInterleaved, // One buffer per channel.
+}
+
+impl Layout {
+    fn to_format_flags(&self) -> sys::AudioFormatFlags {
+        match self {
+            Layout::Interleaved => 0,
+            Layout::NonInterleaved => sys::kLinearPCMFormatFlagIsNonInterleaved,
+        }
     }
 }
 
-struct Parameters {
-    channels: u32,
-    format: Format,
-    rate: f64,
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameters {
+    pub channels: u32,
+    pub format: Format,
+    pub rate: f64,
+    pub layout: Layout,
 }
 impl Parameters {
-    fn new(channels: u32, format: Format, rate: f64) -> Self {
+    // The channels are non-interleaved by default.
+    pub fn new(channels: u32, format: Format, rate: f64) -> Self {
         Parameters {
             channels,
             format,
             rate,
+            layout: Layout::NonInterleaved,
         }
     }
+
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
     fn to_description(&self) -> sys::AudioStreamBasicDescription {
         let byte_size = self.format.byte_size() as u32;
-        let bits_per_channel = byte_size * 8;
+        let bits_per_channel = self.format.bits_per_channel();
         let frames_per_packet = 1;
-        // The channels in the buffer is set to non-interleaved by
-        // `to_format_flags`. Therefore,
-        // 1. `bytes_per_frame` is same as `byte_size`.
-        // 2. `AudioBufferList.mNumberBuffers` received in
-        //    `audio_unit_callback` is same as the number of
-        //    channels we have.
-        let bytes_per_frame = byte_size;
+        // For the non-interleaved layout, `mBytesPerFrame` is the size of one
+        // channel's sample and `AudioBufferList.mNumberBuffers` received in
+        // `audio_unit_callback` is same as the number of channels. For the
+        // interleaved layout, there is only one buffer and a frame contains
+        // the samples of all the channels.
+        let bytes_per_frame = match self.layout {
+            Layout::Interleaved => byte_size * self.channels,
+            Layout::NonInterleaved => byte_size,
+        };
         let bytes_per_packet = bytes_per_frame * frames_per_packet;
         sys::AudioStreamBasicDescription {
             mSampleRate: self.rate,
             mFormatID: sys::kAudioFormatLinearPCM,
-            mFormatFlags: self.format.to_format_flags(),
+            mFormatFlags: self.format.to_format_flags() | self.layout.to_format_flags(),
             mBytesPerPacket: bytes_per_packet,
             mFramesPerPacket: frames_per_packet,
             mBytesPerFrame: bytes_per_frame,
@@ -89,103 +220,476 @@ impl Parameters {
 struct AudioData<T> {
     buffers: &'static mut [sys::AudioBuffer], // The list of audio buffers.
     frames: usize,                            // The number of frames in each channel.
+    timing: Timing,                           // The timing of the first frame.
     data_type: PhantomData<T>,
 }
 
 pub type CallbackArgs<'a, T> = &'a mut [&'a mut [T]];
-type Callback<T> = fn(CallbackArgs<T>);
-
-// The Stream struct will be converted to a pointer and the pointer will be
-// set as a `custom data` pointer to the underlying `AudioUnit` callback
-// function. (see `inputProcRefCon` in `set_callback`). Since underlying
-// function is implemented in C, using `#[repr(C)]` to prevent the struct
-// layout of `Stream` from being mangled by Rust compiler.
-#[repr(C)]
-pub struct Stream<T> {
-    callback: Callback<T>,
-    parameters: Parameters,
+// The callback owns its state, so no global or lock is needed to keep the
+// state between the calls. The `Timing` tells when the data in the buffers
+// will be played.
+//
+// The callback returns the number of frames it produced. Returning fewer
+// frames than the buffers have signals the end of the stream. The rest of the
+// buffers is filled with silence, and the callback won't be called again.
+type Callback<T> = Box<dyn FnMut(CallbackArgs<T>, &Timing) -> usize + Send>;
+type InterleavedCallback<T> = Box<dyn FnMut(InterleavedBuffer<T>, &Timing) -> usize + Send>;
+// Called once all the produced frames have been heard after the end of the
+// stream.
+type DrainedCallback = Box<dyn FnOnce() + Send>;
+// Called with the new state when the state of the stream changes. It's
+// called on the audio thread for `State::Drained` and `State::Error`, and on
+// the HAL notification thread for `State::DeviceLost`.
+type StateCallback = Box<dyn FnMut(State) + Send>;
+// Called on the HAL notification thread with the new device after the stream
+// switches to it.
+type DeviceChangedCallback = Box<dyn FnMut(&AudioObject) + Send>;
+
+// The callback matching the layout of the stream.
+enum StreamCallback<T> {
+    Interleaved(InterleavedCallback<T>),
+    NonInterleaved(Callback<T>),
+}
+
+// The callback and the data it needs are put in a heap-allocated context, and
+// the pointer to the context is set as the `custom data` pointer of the
+// underlying `AudioUnit` callback function (see `inputProcRefCon` in
+// `set_callback`). The address of the context is stable no matter where the
+// `Stream` is moved to, so the stream can be fully initialized in `new`.
+pub struct Stream<T: Sample> {
+    // The stream follows the default output device when it's `None`.
+    device: Option<AudioObject>,
+    // The listeners are removed before the context is dropped.
+    device_listeners: Vec<PropertyListener>,
+    route_listeners: Vec<PropertyListener>,
+    // The thread stopping the unit after the stream is drained. It's joined
+    // before the context is dropped.
+    stopper: Option<JoinHandle<()>>,
+    context: Box<StreamContext<T>>,
+}
+
+// The unit is in the context so the stopper thread can stop it after the
+// stream is drained. The context is used by the audio thread, the thread
+// owning the stream and the HAL notification thread at the same time, so it's
+// only ever borrowed as `&StreamContext`. The fields shared by the threads
+// are atomic or locked.
+struct StreamContext<T> {
     unit: AudioUnit,
+    parameters: Parameters,
+    // The output latency of the device in use, in frames.
+    latency: AtomicUsize,
+    position: Position,
+    volume: Volume,
+    state: AtomicState,
+    state_callback: Mutex<Option<StateCallback>>,
+    pending_states: PendingStates,
+    drained: AtomicBool,
+    drained_callback: Mutex<Option<DrainedCallback>>,
+    auto_stop: AtomicBool,
+    // The unit can't be stopped from its own callback, so the audio thread
+    // asks the stopper thread to stop it. The stopper thread exits once
+    // `closing` is set.
+    stopper: Mutex<Option<Thread>>,
+    stop_requested: AtomicBool,
+    closing: AtomicBool,
+    // Set when the stream starts, so the audio thread forgets the end of the
+    // stream before the next buffer.
+    restart: AtomicBool,
+    // The device the unit plays on. It's locked while the unit is
+    // reconfigured, so `start`, `stop` and `set_volume` wait for it.
+    route: Mutex<AudioObject>,
+    device_changed_callback: Mutex<Option<DeviceChangedCallback>>,
+    render_state: UnsafeCell<RenderState<T>>,
+}
+
+// The state of the rendering. The audio thread is its only reader and writer:
+// it's only borrowed in `StreamContext::render`, which the unit never calls
+// concurrently, and no other thread touches it once the callback is set.
+struct RenderState<T> {
+    callback: StreamCallback<T>,
+    drain: Drain,
+    position: PositionTracker,
+    gain: Gain,
+    auto_stopped: bool,
+    channel_buffers: SliceTable<T>,
+}
+
+// The pointer to the context used by the listeners on the HAL notification
+// thread and by the stopper thread. The listeners are removed and the stopper
+// thread is joined before the context is dropped.
+struct ContextPtr<T>(*const StreamContext<T>);
+
+unsafe impl<T> Send for ContextPtr<T> {}
+
+impl<T> ContextPtr<T> {
+    fn get(&self) -> &StreamContext<T> {
+        unsafe { &*self.0 }
+    }
 }
 
 // Learn AUHAL concepts of `scope` and `bus (element)` from below link:
 // https://developer.apple.com/library/archive/technotes/tn2091/_index.html
 // This gives idea about how we set the audio stream here.
-impl<T> Stream<T> {
-    pub fn new(
+impl<T: Sample> Stream<T> {
+    // Play on the default output device. The stream switches to the new
+    // default device when the default device changes or the device in use is
+    // removed, and keeps its callback, format, volume and state.
+    pub fn new<F>(channels: u32, rate: f64, callback: F) -> Result<Self, Error>
+    where
+        F: FnMut(CallbackArgs<T>, &Timing) -> usize + Send + 'static,
+    {
+        let unit = AudioUnit::new_hal()?;
+        Self::create(
+            unit,
+            None,
+            Parameters::new(channels, T::FORMAT, rate),
+            StreamCallback::NonInterleaved(Box::new(callback)),
+        )
+    }
+
+    // Same as `new` but the callback gets the data of all the channels in one
+    // interleaved buffer.
+    pub fn new_interleaved<F>(channels: u32, rate: f64, callback: F) -> Result<Self, Error>
+    where
+        F: FnMut(InterleavedBuffer<T>, &Timing) -> usize + Send + 'static,
+    {
+        let unit = AudioUnit::new_hal()?;
+        Self::create(
+            unit,
+            None,
+            Parameters::new(channels, T::FORMAT, rate).with_layout(Layout::Interleaved),
+            StreamCallback::Interleaved(Box::new(callback)),
+        )
+    }
+
+    // Play on the given device. Unlike the stream created by `new`, it can't
+    // follow the changes of the default output device.
+    pub fn with_device<F>(
+        device: &AudioObject,
         channels: u32,
-        format: Format,
         rate: f64,
-        callback: Callback<T>,
-    ) -> Result<Self, Error> {
-        assert_eq!(format.byte_size(), size_of::<T>());
-        let parameters = Parameters::new(channels, format, rate);
-        let unit = AudioUnit::new()?;
-        let stm = Stream {
-            callback,
-            parameters,
+        callback: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(CallbackArgs<T>, &Timing) -> usize + Send + 'static,
+    {
+        check_scope(device, &Scope::Output)?;
+        let unit = AudioUnit::new_hal()?;
+        Self::create(
+            unit,
+            Some(device.clone()),
+            Parameters::new(channels, T::FORMAT, rate),
+            StreamCallback::NonInterleaved(Box::new(callback)),
+        )
+    }
+
+    pub fn with_device_interleaved<F>(
+        device: &AudioObject,
+        channels: u32,
+        rate: f64,
+        callback: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(InterleavedBuffer<T>, &Timing) -> usize + Send + 'static,
+    {
+        check_scope(device, &Scope::Output)?;
+        let unit = AudioUnit::new_hal()?;
+        Self::create(
             unit,
+            Some(device.clone()),
+            Parameters::new(channels, T::FORMAT, rate).with_layout(Layout::Interleaved),
+            StreamCallback::Interleaved(Box::new(callback)),
+        )
+    }
+
+    // Return `Error::InvalidState` if the stream is started, drained or
+    // in error. Return `Error::DeviceLost` once the device is lost.
+    //
+    // A stream ended by its callback plays again after it's stopped and
+    // started: the callback is called again and the stream is no longer
+    // drained. The drained callback is called once, so it needs to be set
+    // again.
+    pub fn start(&self) -> Result<(), Error> {
+        let route = self.context.route.lock().unwrap();
+        let previous = self
+            .context
+            .state
+            .transit(&[State::Initialized, State::Stopped], State::Started)
+            .map_err(to_state_error)?;
+        self.context.position.rebase();
+        let drained = self.context.drained.swap(false, Ordering::SeqCst);
+        self.context.restart.store(true, Ordering::SeqCst);
+        if let Err(e) = self.context.unit.start() {
+            // Nothing happens if the unit fails to start.
+            self.context.restart.store(false, Ordering::SeqCst);
+            self.context.drained.store(drained, Ordering::SeqCst);
+            self.context.state.set(previous);
+            return Err(e.into());
+        }
+        // The state callback may start or stop the stream.
+        drop(route);
+        self.context.notify_state(State::Started);
+        Ok(())
+    }
+
+    // Return `Error::InvalidState` if the stream isn't started or drained. A
+    // stream in error is stopped but stays in error. Return
+    // `Error::DeviceLost` once the device is lost.
+    pub fn stop(&self) -> Result<(), Error> {
+        let route = self.context.route.lock().unwrap();
+        match self.get_state() {
+            State::Error => {
+                self.context.unit.stop()?;
+                return Ok(());
+            }
+            State::DeviceLost => return Err(Error::DeviceLost),
+            _ => {}
+        }
+        self.context
+            .state
+            .transit(&[State::Started, State::Drained], State::Stopped)
+            .map_err(to_state_error)?;
+        let result = self.context.unit.stop();
+        if result.is_err() {
+            self.context.state.set(State::Error);
+        }
+        // The state callback may start or stop the stream.
+        drop(route);
+        match result {
+            Ok(()) => {
+                self.context.notify_state(State::Stopped);
+                Ok(())
+            }
+            Err(e) => {
+                self.context.notify_state(State::Error);
+                Err(e.into())
+            }
+        }
+    }
+
+    pub fn get_state(&self) -> State {
+        self.context.state.get()
+    }
+
+    // Set the callback notified for every state change after this call.
+    pub fn set_state_callback<F>(&self, callback: F)
+    where
+        F: FnMut(State) + Send + 'static,
+    {
+        *self.context.state_callback.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    // Whether all the frames produced by the callback have been heard after
+    // the callback signals the end of the stream. A drained stream keeps
+    // playing silence until it's stopped.
+    pub fn is_drained(&self) -> bool {
+        self.context.drained.load(Ordering::SeqCst)
+    }
+
+    // Set the notification called on the audio thread once the stream is
+    // drained. The stream stops itself after the notification if `auto_stop`
+    // is `true`. The notification is called immediately if the stream is
+    // already drained.
+    pub fn set_drained_callback<F>(&self, callback: F, auto_stop: bool)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.context.auto_stop.store(auto_stop, Ordering::SeqCst);
+        *self.context.drained_callback.lock().unwrap() = Some(Box::new(callback));
+        if self.is_drained() {
+            if let Some(callback) = self.context.take_drained_callback() {
+                callback();
+            }
+        }
+    }
+
+    // The number of frames rendered to the device since the stream was
+    // created. The silence played after the end of the stream isn't counted.
+    pub fn position(&self) -> u64 {
+        self.context.position.get()
+    }
+
+    // The number of frames between the frames rendered by the stream and the
+    // frames heard right now, so `position() - latency()` is about the frame
+    // the listener hears. It's the latency of the unit plus the latency of
+    // the device and its stream.
+    pub fn latency(&self) -> Result<u64, Error> {
+        let unit_latency: f64 = self.context.unit.get_property(
+            sys::kAudioUnitProperty_Latency,
+            sys::kAudioUnitScope_Global,
+            Element::Output,
+        )?;
+        let device_latency = get_device_latency_in_seconds(&self.context.route.lock().unwrap())?;
+        let seconds = unit_latency + device_latency;
+        Ok((seconds * self.context.parameters.rate).ceil() as u64)
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.context.volume.get()
+    }
+
+    // Set the volume of the stream, from 0.0 to 1.0. The volume out of the
+    // range is clamped, and a NaN volume mutes the stream. The unit applies
+    // the volume if it can. Otherwise, e.g., when the unit is being torn down
+    // after its device is gone, the volume is applied to the samples from the
+    // callback. The volume is applied again after the stream switches to
+    // another device.
+    pub fn set_volume(&self, volume: f32) {
+        let volume = if volume.is_nan() {
+            0.0
+        } else {
+            volume.clamp(0.0, 1.0)
         };
-        // Don't initialize the stream here!
-        // The memory address of `stm` is different from `x`
-        // where x is returned `stm` outside by `x = Stream::new(...)`.
-        // If we call `stm.init()` or `stm.set_callback()` here, the `self` of
-        // `stm.init()` or `set_callback` are `stm` and it will be freed
-        // after `stm` is returned from `new`. Hence the `inputProcRefCon`
-        // in `set_callback` will be assigned to a dangling pointer and lead
-        // a segment fault or bus error when trying to use `in_ref_con`, which
-        // is a pointer pointing a freed memory chunk, in
-        // `audio_unit_callback`.
+        let _route = self.context.route.lock().unwrap();
+        self.context.set_volume(volume);
+    }
+
+    // Set the callback called on the HAL notification thread after the
+    // stream switches to a new default output device. Return
+    // `Error::FixedDevice` if the stream is created with a device.
+    pub fn set_device_changed_callback<F>(&self, callback: F) -> Result<(), Error>
+    where
+        F: FnMut(&AudioObject) + Send + 'static,
+    {
+        if self.device.is_some() {
+            return Err(Error::FixedDevice);
+        }
+        *self.context.device_changed_callback.lock().unwrap() = Some(Box::new(callback));
+        Ok(())
+    }
+
+    fn create(
+        unit: AudioUnit,
+        device: Option<AudioObject>,
+        parameters: Parameters,
+        callback: StreamCallback<T>,
+    ) -> Result<Self, Error> {
+        let current = match device {
+            Some(ref device) => device.clone(),
+            None => get_default_device(&Scope::Output)?,
+        };
+        let gain = Gain::new(parameters.rate);
+        let channel_buffers = SliceTable::new(parameters.channels as usize);
+        let mut stm = Stream {
+            device,
+            device_listeners: Vec::new(),
+            route_listeners: Vec::new(),
+            stopper: None,
+            context: Box::new(StreamContext {
+                unit,
+                parameters,
+                latency: AtomicUsize::new(0),
+                position: Position::new(),
+                volume: Volume::new(),
+                state: AtomicState::new(State::Initialized),
+                state_callback: Mutex::new(None),
+                pending_states: PendingStates::new(),
+                drained: AtomicBool::new(false),
+                drained_callback: Mutex::new(None),
+                auto_stop: AtomicBool::new(false),
+                stopper: Mutex::new(None),
+                stop_requested: AtomicBool::new(false),
+                closing: AtomicBool::new(false),
+                restart: AtomicBool::new(false),
+                route: Mutex::new(current.clone()),
+                device_changed_callback: Mutex::new(None),
+                render_state: UnsafeCell::new(RenderState {
+                    callback,
+                    drain: Drain::new(),
+                    position: PositionTracker::new(),
+                    gain,
+                    auto_stopped: false,
+                    channel_buffers,
+                }),
+            }),
+        };
+        stm.start_stopper();
+        stm.init(&current)?;
+        if stm.device.is_some() {
+            stm.watch_device(&current)?;
+        } else {
+            stm.follow_default_device()?;
+        }
         Ok(stm)
     }
 
-    pub fn init(&mut self) -> Result<(), Error> {
-        self.set_stream_format()?;
+    // Wake up for every stop request until the stream is dropped. The thread
+    // may wake up spuriously, so it checks the flags every time.
+    fn start_stopper(&mut self) {
+        let context = ContextPtr(&*self.context);
+        let handle = thread::spawn(move || loop {
+            thread::park();
+            let context = context.get();
+            if context.closing.load(Ordering::SeqCst) {
+                break;
+            }
+            if context.stop_requested.swap(false, Ordering::SeqCst) {
+                context.stop_drained();
+            }
+        });
+        *self.context.stopper.lock().unwrap() = Some(handle.thread().clone());
+        self.stopper = Some(handle);
+    }
+
+    fn init(&mut self, device: &AudioObject) -> Result<(), Error> {
+        enable_io(&self.context.unit, false, true)?;
+        set_device(&self.context.unit, device)?;
+        self.context.set_latency(device);
+        self.context.set_stream_format()?;
         self.set_callback()?;
         self.init_unit()?;
         Ok(())
     }
 
-    pub fn start(&self) -> Result<(), Error> {
-        self.unit.start()?;
+    // Watch the device in use so the stream knows when the device is lost.
+    fn watch_device(&mut self, device: &AudioObject) -> Result<(), Error> {
+        let context = ContextPtr(&*self.context);
+        let watched = device.clone();
+        let alive_listener = device
+            .add_alive_listener(move || context.get().check_device(&watched))
+            .map_err(|e| Error::Utils(e.into()))?;
+        let context = ContextPtr(&*self.context);
+        let watched = device.clone();
+        let devices_listener =
+            utils::add_devices_listener(move || context.get().check_device(&watched))?;
+        self.device_listeners = vec![alive_listener, devices_listener];
         Ok(())
     }
 
-    pub fn stop(&self) -> Result<(), Error> {
-        self.unit.stop()?;
+    // Switch to the new default output device when the default device changes
+    // or the device list changes. A lost device is replaced by the new
+    // default device instead of moving the stream to `DeviceLost`.
+    fn follow_default_device(&mut self) -> Result<(), Error> {
+        let context = ContextPtr(&*self.context);
+        let default_device_listener =
+            utils::add_default_device_listener(&Scope::Output, move || context.get().reroute())?;
+        let context = ContextPtr(&*self.context);
+        let devices_listener = utils::add_devices_listener(move || context.get().reroute())?;
+        self.route_listeners = vec![default_device_listener, devices_listener];
         Ok(())
     }
 
     fn init_unit(&self) -> Result<(), Error> {
-        self.unit.initialize()?;
+        self.context.unit.initialize()?;
         Ok(())
     }
 
     fn uninit_unit(&self) -> Result<(), Error> {
-        self.unit.uninitialize()?;
-        Ok(())
-    }
-
-    fn set_stream_format(&self) -> Result<(), Error> {
-        let description = self.parameters.to_description();
-        self.unit.set_property(
-            sys::kAudioUnitProperty_StreamFormat,
-            sys::kAudioUnitScope_Input,
-            Element::Output,
-            &description,
-        )?;
+        self.context.unit.uninitialize()?;
         Ok(())
     }
 
     // Reference:
     // https://developer.apple.com/documentation/audiotoolbox/aurendercallbackstruct?language=objc
     // https://developer.apple.com/documentation/audiotoolbox/aurendercallback?language=objc
-    fn set_callback(&mut self) -> Result<(), Error> {
+    fn set_callback(&self) -> Result<(), Error> {
+        let context: &StreamContext<T> = &self.context;
         let callback_struct = sys::AURenderCallbackStruct {
-            inputProc: Some(Self::audio_unit_callback),
-            inputProcRefCon: self as *mut Self as *mut c_void,
+            inputProc: Some(StreamContext::<T>::audio_unit_callback),
+            inputProcRefCon: context as *const StreamContext<T> as *mut c_void,
         };
 
-        self.unit.set_property(
+        context.unit.set_property(
             sys::kAudioUnitProperty_SetRenderCallback,
             sys::kAudioUnitScope_Input,
             Element::Output,
@@ -193,7 +697,9 @@ impl<T> Stream<T> {
         )?;
         Ok(())
     }
+}
 
+impl<T: Sample> StreamContext<T> {
     fn render(
         &self,
         io_action_flags: *mut sys::AudioUnitRenderActionFlags,
@@ -206,32 +712,314 @@ impl<T> Stream<T> {
         // to know why it's necessary to use `&mut` to get the data inside `io_data`.
         let buffers = unsafe {
             let ptr = (&mut (*io_data)).mBuffers.as_ptr() as *mut sys::AudioBuffer;
-            let len = (&mut (*io_data)).mNumberBuffers as usize; // interleaved channels.
+            let len = (&mut (*io_data)).mNumberBuffers as usize; // One buffer per channel if non-interleaved.
             slice::from_raw_parts_mut(ptr, len)
         };
         let data = AudioData {
             buffers: buffers,
             frames: in_number_of_frames as usize,
+            timing: get_timing(in_time_stamp),
             data_type: PhantomData,
         };
-        self.get_buffer_data(data)
+        // The audio thread is the only one using the render state.
+        let render_state = unsafe { &mut *self.render_state.get() };
+        self.get_buffer_data(render_state, data)
     }
 
-    fn get_buffer_data(&self, data: AudioData<T>) -> sys::OSStatus {
-        assert_eq!(data.buffers.len() as u32, self.parameters.channels);
-        let mut channel_buffers = Vec::new();
-        for buffer in data.buffers {
-            assert_eq!(buffer.mNumberChannels, 1);
-            assert_eq!((data.frames * size_of::<T>()) as u32, buffer.mDataByteSize);
-            let ptr = buffer.mData as *mut T;
-            let len = data.frames;
-            let channel_buffer = unsafe { slice::from_raw_parts_mut(ptr, len) };
-            channel_buffers.push(channel_buffer);
+    fn get_buffer_data(
+        &self,
+        render_state: &mut RenderState<T>,
+        data: AudioData<T>,
+    ) -> sys::OSStatus {
+        self.notify_pending_states();
+        if self.restart.swap(false, Ordering::SeqCst) {
+            render_state.drain.reset();
+            render_state.auto_stopped = false;
+        }
+        if self.state.get().is_error() {
+            fill_silence::<T>(data.buffers, 0);
+            return sys::noErr as sys::OSStatus;
+        }
+        if render_state.drain.is_draining() {
+            fill_silence::<T>(data.buffers, 0);
+            if render_state.drain.advance(data.frames) {
+                self.on_drained(render_state);
+            }
+            return sys::noErr as sys::OSStatus;
+        }
+
+        let channels = self.parameters.channels;
+        let produced = match render_state.callback {
+            StreamCallback::Interleaved(ref mut callback) => {
+                let len = data.frames * channels as usize;
+                let valid = data.buffers.len() == 1
+                    && data.buffers[0].mNumberChannels == channels
+                    && data.buffers[0].mDataByteSize == (len * size_of::<T>()) as u32;
+                if !valid {
+                    return self.on_error(data.buffers);
+                }
+                let ptr = data.buffers[0].mData as *mut T;
+                let samples = unsafe { slice::from_raw_parts_mut(ptr, len) };
+                callback(
+                    InterleavedBuffer::new(samples, channels as usize),
+                    &data.timing,
+                )
+            }
+            StreamCallback::NonInterleaved(ref mut callback) => {
+                let byte_size = (data.frames * size_of::<T>()) as u32;
+                let valid = data.buffers.len() as u32 == channels
+                    && data.buffers.iter().all(|buffer| {
+                        buffer.mNumberChannels == 1 && buffer.mDataByteSize == byte_size
+                    });
+                if !valid {
+                    return self.on_error(data.buffers);
+                }
+                let timing = &data.timing;
+                let pointers = data.buffers.iter().map(|buffer| buffer.mData as *mut T);
+                unsafe {
+                    render_state
+                        .channel_buffers
+                        .with_slices(pointers, data.frames, |buffers| callback(buffers, timing))
+                }
+            }
+        };
+
+        // Producing more frames than requested is same as producing all.
+        let produced_frames = produced.min(data.frames);
+        render_state
+            .gain
+            .apply::<T>(&self.volume, data.buffers, produced_frames);
+        render_state
+            .position
+            .update(&self.position, &data.timing, produced_frames);
+        if produced < data.frames {
+            fill_silence::<T>(data.buffers, produced);
+            let latency = self.latency.load(Ordering::SeqCst);
+            render_state.drain.start(produced, data.frames, latency);
         }
-        (self.callback)(&mut channel_buffers);
         sys::noErr as sys::OSStatus
     }
 
+    // The buffers don't match the stream parameters. Play silence instead of
+    // calling the callback with the wrong buffers.
+    fn on_error(&self, buffers: &mut [sys::AudioBuffer]) -> sys::OSStatus {
+        fill_silence::<T>(buffers, 0);
+        if self.state.transit(&[State::Started], State::Error).is_ok() {
+            self.notify_state_without_blocking(State::Error);
+        }
+        sys::kAudioUnitErr_InvalidParameter
+    }
+
+    // Called on the audio thread for each buffer after the stream is drained.
+    // The notification is retried in the next buffer if it's being set.
+    fn on_drained(&self, render_state: &mut RenderState<T>) {
+        self.drained.store(true, Ordering::SeqCst);
+        if self
+            .state
+            .transit(&[State::Started], State::Drained)
+            .is_ok()
+        {
+            self.notify_state_without_blocking(State::Drained);
+        }
+        if let Some(callback) = self.take_drained_callback() {
+            callback();
+        }
+        if self.auto_stop.load(Ordering::SeqCst) && !render_state.auto_stopped {
+            render_state.auto_stopped = self.request_stop();
+        }
+    }
+
+    // Called on the audio thread. Return `false` if the stopper thread can't
+    // be woken up without blocking, so the request is retried in the next
+    // buffer.
+    fn request_stop(&self) -> bool {
+        match self.stopper.try_lock() {
+            Ok(stopper) => match *stopper {
+                Some(ref stopper) => {
+                    self.stop_requested.store(true, Ordering::SeqCst);
+                    stopper.unpark();
+                    true
+                }
+                None => false,
+            },
+            Err(_) => false,
+        }
+    }
+
+    // Called on the stopper thread. The stream may be stopped or started
+    // again since the request, so the unit is only stopped if the stream is
+    // still drained.
+    fn stop_drained(&self) {
+        let _route = self.route.lock().unwrap();
+        if self.state.get() == State::Drained {
+            let _ = self.unit.stop();
+        }
+    }
+
+    fn set_stream_format(&self) -> Result<(), Error> {
+        let description = self.parameters.to_description();
+        self.unit.set_property(
+            sys::kAudioUnitProperty_StreamFormat,
+            sys::kAudioUnitScope_Input,
+            Element::Output,
+            &description,
+        )?;
+        Ok(())
+    }
+
+    fn set_volume(&self, volume: f32) {
+        let by_unit = self
+            .unit
+            .set_parameter(
+                sys::kHALOutputParam_Volume,
+                sys::kAudioUnitScope_Global,
+                Element::Output,
+                volume,
+            )
+            .is_ok();
+        self.volume.set(volume, by_unit);
+    }
+
+    fn set_latency(&self, device: &AudioObject) {
+        let latency = get_latency_frames(device, self.parameters.rate);
+        self.latency.store(latency, Ordering::SeqCst);
+    }
+
+    // Called on the HAL notification thread when the default device or the
+    // device list changes. Nothing is done if the default device is still the
+    // one in use. The device is lost if there is no default device anymore,
+    // and the stream is in error if it fails to switch. The user callbacks are
+    // called after `route` is unlocked, so they can start or stop the stream.
+    fn reroute(&self) {
+        let mut route = self.route.lock().unwrap();
+        let device = match get_default_device(&Scope::Output) {
+            Ok(device) => device,
+            Err(_) => {
+                let lost = self.set_device_lost();
+                drop(route);
+                if lost {
+                    self.notify_state(State::DeviceLost);
+                }
+                return;
+            }
+        };
+        if *route == device {
+            return;
+        }
+        if self.reinit_unit(&device).is_err() {
+            let running = [State::Started, State::Drained];
+            let failed = self.state.transit(&running, State::Error).is_ok();
+            drop(route);
+            if failed {
+                self.notify_state(State::Error);
+            }
+            return;
+        }
+        *route = device.clone();
+        drop(route);
+        if let Some(ref mut callback) = *self.device_changed_callback.lock().unwrap() {
+            callback(&device);
+        }
+    }
+
+    // Reinitialize the unit so it plays on the given device. The unit is
+    // restarted if it was running.
+    fn reinit_unit(&self, device: &AudioObject) -> Result<(), Error> {
+        let running = matches!(self.state.get(), State::Started | State::Drained);
+        if running {
+            self.unit.stop()?;
+        }
+        self.unit.uninitialize()?;
+        set_device(&self.unit, device)?;
+        self.set_latency(device);
+        self.set_stream_format()?;
+        self.unit.initialize()?;
+        self.set_volume(self.volume.get());
+        if running {
+            self.position.rebase();
+            self.unit.start()?;
+        }
+        Ok(())
+    }
+
+    // Called on the HAL notification thread when the device dies or the device
+    // list changes.
+    fn check_device(&self, device: &AudioObject) {
+        if !device.is_alive().unwrap_or(false) {
+            self.on_device_lost();
+        }
+    }
+
+    // The state callback is called after `route` is unlocked.
+    fn on_device_lost(&self) {
+        let lost = {
+            let _route = self.route.lock().unwrap();
+            self.set_device_lost()
+        };
+        if lost {
+            self.notify_state(State::DeviceLost);
+        }
+    }
+
+    // Called with `route` locked. Return `true` if the device is lost just
+    // now. The callback isn't called anymore once the device is lost, and the
+    // unit is left for `Drop` to clean up.
+    fn set_device_lost(&self) -> bool {
+        let alive = [
+            State::Initialized,
+            State::Started,
+            State::Stopped,
+            State::Drained,
+        ];
+        self.state.transit(&alive, State::DeviceLost).is_ok()
+    }
+
+    fn notify_state(&self, state: State) {
+        if let Some(ref mut callback) = *self.state_callback.lock().unwrap() {
+            while let Some(pending) = self.pending_states.pop() {
+                callback(pending);
+            }
+            callback(state);
+        }
+    }
+
+    // Called on the audio thread. The state is kept in `pending_states` if
+    // the callback is in use, and notified later in `notify_pending_states`
+    // or before the next notification.
+    fn notify_state_without_blocking(&self, state: State) {
+        match self.state_callback.try_lock() {
+            Ok(mut callback) => {
+                if let Some(ref mut callback) = *callback {
+                    while let Some(pending) = self.pending_states.pop() {
+                        callback(pending);
+                    }
+                    callback(state);
+                }
+            }
+            Err(_) => self.pending_states.push(state),
+        }
+    }
+
+    // Called on the audio thread. The states are only taken with the
+    // callback locked, so they're popped by one thread at a time.
+    fn notify_pending_states(&self) {
+        if let Ok(mut callback) = self.state_callback.try_lock() {
+            if let Some(ref mut callback) = *callback {
+                while let Some(pending) = self.pending_states.pop() {
+                    callback(pending);
+                }
+            }
+        }
+    }
+
+    // Never block the audio thread for the notification.
+    fn take_drained_callback(&self) -> Option<DrainedCallback> {
+        match self.drained_callback.try_lock() {
+            Ok(mut callback) => callback.take(),
+            Err(_) => None,
+        }
+    }
+
     // The *static* callback function that will be registered into
     // `AURenderCallbackStruct` and called by underlying `AudioUnit`
     // framework directly.
@@ -243,7 +1031,7 @@ impl<T> Stream<T> {
         in_number_of_frames: sys::UInt32,
         io_data: *mut sys::AudioBufferList,
     ) -> sys::OSStatus {
-        let render_callback_object = in_ref_con as *mut Self;
+        let render_callback_object = in_ref_con as *const Self;
         unsafe {
             (*render_callback_object).render(
                 io_action_flags,
@@ -256,9 +1044,82 @@ impl<T> Stream<T> {
     }
 }
 
-impl<T> Drop for Stream<T> {
+// Fill the buffers with silence from the given frame to the end.
+fn fill_silence<T: Sample>(buffers: &mut [sys::AudioBuffer], from_frame: usize) {
+    for buffer in buffers.iter_mut() {
+        let len = buffer.mDataByteSize as usize / size_of::<T>();
+        let samples = unsafe { slice::from_raw_parts_mut(buffer.mData as *mut T, len) };
+        let from = (from_frame * buffer.mNumberChannels as usize).min(len);
+        for sample in samples[from..].iter_mut() {
+            *sample = T::SILENCE;
+        }
+    }
+}
+
+// Get the output latency of the device in the frames of the stream. Return 0
+// if the latency is unknown.
+fn get_latency_frames(device: &AudioObject, rate: f64) -> usize {
+    match device.get_hardware_latency_in_seconds(&Scope::Output) {
+        Ok(seconds) => (seconds * rate).ceil() as usize,
+        Err(_) => 0,
+    }
+}
+
+// Get the output latency of the device, including the latency of its stream.
+fn get_device_latency_in_seconds(device: &AudioObject) -> Result<f64, Error> {
+    device
+        .get_hardware_latency_in_seconds(&Scope::Output)
+        .map_err(|e| Error::Utils(e.into()))
+}
+
+// The error for the operation not allowed in the given state.
+fn to_state_error(state: State) -> Error {
+    match state {
+        State::DeviceLost => Error::DeviceLost,
+        state => Error::InvalidState(state),
+    }
+}
+
+// Return `Error::UnsupportedRate` if the rate isn't the nominal rate of the
+// input device. The AUHAL unit converts the rate of the data played on the
+// output device, but not the rate of the data captured from the input device.
+fn check_input_rate(device: &AudioObject, rate: f64) -> Result<(), Error> {
+    let device_rate = device
+        .get_rate(&Scope::Input)
+        .map_err(|e| Error::Utils(e.into()))?;
+    if device_rate != rate {
+        return Err(Error::UnsupportedRate(device_rate));
+    }
+    Ok(())
+}
+
+// Return `Error::WrongScope` if the device has no stream in the given scope.
+fn check_scope(device: &AudioObject, scope: &Scope) -> Result<(), Error> {
+    match device.in_scope(scope) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(Error::WrongScope),
+        Err(e) => Err(Error::Utils(e.into())),
+    }
+}
+
+// Stop the unit regardless of the state. No state change is notified. Don't
+// panic here since the unit may fail to stop once the device is lost or after
+// a failed switch, or the stream may be dropped while unwinding.
+impl<T: Sample> Drop for Stream<T> {
     fn drop(&mut self) {
-        assert!(self.stop().is_ok());
-        assert!(self.uninit_unit().is_ok());
+        self.device_listeners.clear();
+        self.route_listeners.clear();
+        let _ = self.context.unit.stop();
+        if let Some(stopper) = self.stopper.take() {
+            self.context.closing.store(true, Ordering::SeqCst);
+            stopper.thread().unpark();
+            let _ = stopper.join();
+        }
+        let _ = self.uninit_unit();
     }
 }
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
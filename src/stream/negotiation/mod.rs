@@ -0,0 +1,197 @@
+extern crate coreaudio_sys as sys;
+
+use super::{Format, Parameters};
+use utils::{
+    AudioObject, Error, RateRange, Scope, StreamFormat, StreamFormatRange, SupportedRates,
+};
+
+// Negotiation
+// ============================================================================
+#[derive(Clone, Debug, PartialEq)]
+pub enum Negotiation {
+    // The device supports the parameters natively.
+    Supported,
+    // The closest configuration the device supports natively.
+    Closest(Parameters),
+    // The device has no channel in the scope, or no format with a rate the
+    // device supports.
+    Unsupported,
+}
+
+// What the device supports natively in a scope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeviceCapabilities {
+    pub channels: u32,
+    pub rates: SupportedRates,
+    pub formats: Vec<StreamFormatRange>,
+}
+
+impl DeviceCapabilities {
+    pub fn new(channels: u32, rates: SupportedRates, formats: Vec<StreamFormatRange>) -> Self {
+        DeviceCapabilities {
+            channels,
+            rates,
+            formats,
+        }
+    }
+
+    pub fn from_device(device: &AudioObject, scope: &Scope) -> Result<Self, Error> {
+        let channels = device.get_channel_count(scope)?;
+        let rates = device.get_supported_rates(scope)?;
+        let mut formats = Vec::new();
+        for stream in device.get_streams(scope)? {
+            formats.extend(stream.get_available_virtual_formats()?);
+        }
+        Ok(DeviceCapabilities::new(channels, rates, formats))
+    }
+}
+
+pub fn negotiate(
+    device: &AudioObject,
+    scope: &Scope,
+    parameters: &Parameters,
+) -> Result<Negotiation, Error> {
+    let capabilities = DeviceCapabilities::from_device(device, scope)?;
+    Ok(negotiate_with_capabilities(parameters, &capabilities))
+}
+
+// The channels are supported if the device has at least that many channels.
+// The closest format is chosen first among the formats the device offers, and
+// the rate is then taken from the rate ranges of that format. A format without
+// any rate the device supports is skipped.
+pub fn negotiate_with_capabilities(
+    parameters: &Parameters,
+    capabilities: &DeviceCapabilities,
+) -> Negotiation {
+    if capabilities.channels == 0 {
+        return Negotiation::Unsupported;
+    }
+    let channels = parameters.channels.min(capabilities.channels);
+    let closest_format = get_supported_formats(&capabilities.formats)
+        .into_iter()
+        .filter_map(|format| {
+            get_nearest_rate(&format, parameters.rate, capabilities).map(|rate| (format, rate))
+        })
+        .min_by_key(|(format, _)| get_format_distance(&parameters.format, format));
+    let (format, rate) = match closest_format {
+        Some(format_and_rate) => format_and_rate,
+        None => return Negotiation::Unsupported,
+    };
+
+    let closest = Parameters::new(channels, format, rate).with_layout(parameters.layout.clone());
+    if &closest == parameters {
+        Negotiation::Supported
+    } else {
+        Negotiation::Closest(closest)
+    }
+}
+
+// Utils
+// ============================================================================
+// Return the `Format`s available in the given ranges without duplicates,
+// in the order they first appear.
+fn get_supported_formats(ranges: &[StreamFormatRange]) -> Vec<Format> {
+    let mut formats = Vec::new();
+    for range in ranges {
+        if let Some(format) = to_format(&range.format) {
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+    }
+    formats
+}
+
+// Return the rate closest to the given rate among the rates both the device
+// and the ranges of the given format support, or `None` if there is no such
+// rate.
+fn get_nearest_rate(format: &Format, rate: f64, capabilities: &DeviceCapabilities) -> Option<f64> {
+    let mut ranges = Vec::new();
+    for range in &capabilities.formats {
+        if to_format(&range.format).as_ref() != Some(format) {
+            continue;
+        }
+        let (format_min, format_max) = range.rate_range;
+        for device_range in capabilities.rates.get_ranges() {
+            let min = format_min.max(device_range.get_min());
+            let max = format_max.min(device_range.get_max());
+            if min == max {
+                ranges.push(RateRange::Discrete(min));
+            } else if min < max {
+                ranges.push(RateRange::Continuous(min, max));
+            }
+        }
+    }
+    SupportedRates::new(ranges).nearest_supported_rate(rate)
+}
+
+// The smaller, the closer the format is to the wanted one. A format of the
+// same kind (float, signed or unsigned integer) is always closer than a
+// format of another kind. Then the closer bit depth, the closer byte size and
+// the same endianness win in order.
+fn get_format_distance(wanted: &Format, format: &Format) -> (bool, u32, usize, bool) {
+    let kind = |format: &Format| match format {
+        Format::U8 => 0,
+        Format::F32LE | Format::F32BE | Format::F64LE | Format::F64BE => 1,
+        _ => 2,
+    };
+    (
+        kind(wanted) != kind(format),
+        wanted
+            .bits_per_channel()
+            .abs_diff(format.bits_per_channel()),
+        wanted.byte_size().abs_diff(format.byte_size()),
+        wanted.is_big_endian() != format.is_big_endian(),
+    )
+}
+
+fn to_format(format: &StreamFormat) -> Option<Format> {
+    if !format.is_linear_pcm() {
+        return None;
+    }
+    let channels_per_frame = if format.is_interleaved() {
+        format.channels
+    } else {
+        1
+    };
+    if channels_per_frame == 0 {
+        return None;
+    }
+    let byte_size = format.bytes_per_frame / channels_per_frame;
+    if byte_size * channels_per_frame != format.bytes_per_frame {
+        return None;
+    }
+    // Only the low-aligned samples are supported if they don't fill the bytes.
+    let aligned_high = format.flags & sys::kLinearPCMFormatFlagIsAlignedHigh != 0;
+    if aligned_high && format.bits_per_channel != byte_size * 8 {
+        return None;
+    }
+    let big_endian = format.is_big_endian();
+    let bits = format.bits_per_channel;
+    let candidates = if format.is_float() {
+        vec![
+            (Format::F32LE, Format::F32BE),
+            (Format::F64LE, Format::F64BE),
+        ]
+    } else if format.is_signed_integer() {
+        vec![
+            (Format::S16LE, Format::S16BE),
+            (Format::S24LE, Format::S24BE),
+            (Format::S24_32LE, Format::S24_32BE),
+            (Format::S32LE, Format::S32BE),
+        ]
+    } else {
+        vec![(Format::U8, Format::U8)]
+    };
+    candidates
+        .into_iter()
+        .map(|(little, big)| if big_endian { big } else { little })
+        .find(|candidate| {
+            candidate.bits_per_channel() == bits && candidate.byte_size() as u32 == byte_size
+        })
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
@@ -0,0 +1,330 @@
+extern crate coreaudio_sys as sys;
+
+use super::*;
+use stream::Layout;
+use utils::RateRange;
+
+fn get_fake_format_range(flags: sys::AudioFormatFlags, bits: u32) -> StreamFormatRange {
+    let bytes = bits / 8;
+    StreamFormatRange {
+        format: StreamFormat {
+            rate: 0.0,
+            format_id: sys::kAudioFormatLinearPCM,
+            flags: flags | sys::kAudioFormatFlagIsPacked,
+            bytes_per_packet: bytes * 2,
+            frames_per_packet: 1,
+            bytes_per_frame: bytes * 2,
+            channels: 2,
+            bits_per_channel: bits,
+        },
+        rate_range: (44_100.0, 48_000.0),
+    }
+}
+
+fn get_fake_capabilities() -> DeviceCapabilities {
+    DeviceCapabilities::new(
+        2,
+        SupportedRates::new(vec![
+            RateRange::Discrete(44_100.0),
+            RateRange::Discrete(48_000.0),
+        ]),
+        vec![get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32)],
+    )
+}
+
+// Tests for Public Functions
+// ============================================================================
+
+// negotiate_with_capabilities
+// ------------------------------------
+#[test]
+fn test_negotiate_supported_parameters() {
+    let capabilities = get_fake_capabilities();
+    let parameters = Parameters::new(2, Format::F32LE, 48_000.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Supported
+    );
+    let parameters = Parameters::new(1, Format::F32LE, 44_100.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Supported
+    );
+}
+
+#[test]
+fn test_negotiate_unsupported_rate() {
+    let capabilities = get_fake_capabilities();
+    let parameters = Parameters::new(2, Format::F32LE, 50_000.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Closest(Parameters::new(2, Format::F32LE, 48_000.0))
+    );
+}
+
+#[test]
+fn test_negotiate_too_many_channels() {
+    let capabilities = get_fake_capabilities();
+    let parameters = Parameters::new(6, Format::F32LE, 44_100.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Closest(Parameters::new(2, Format::F32LE, 44_100.0))
+    );
+}
+
+#[test]
+fn test_negotiate_keeps_layout() {
+    let capabilities = get_fake_capabilities();
+    let parameters = Parameters::new(6, Format::F32LE, 44_100.0).with_layout(Layout::Interleaved);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Closest(
+            Parameters::new(2, Format::F32LE, 44_100.0).with_layout(Layout::Interleaved)
+        )
+    );
+}
+
+#[test]
+fn test_negotiate_unsupported_format() {
+    let capabilities = get_fake_capabilities();
+    let parameters = Parameters::new(2, Format::S16LE, 96_000.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Closest(Parameters::new(2, Format::F32LE, 48_000.0))
+    );
+}
+
+#[test]
+fn test_negotiate_closest_format() {
+    // The float format is closer to the wanted double format than the
+    // integer format listed first.
+    let mut capabilities = get_fake_capabilities();
+    capabilities.formats = vec![
+        get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 16),
+        get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32),
+    ];
+    let parameters = Parameters::new(2, Format::F64LE, 48_000.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Closest(Parameters::new(2, Format::F32LE, 48_000.0))
+    );
+    // The 24-bit samples are closer to the 32-bit ones than the 16-bit ones.
+    capabilities.formats = vec![
+        get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 16),
+        get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 24),
+    ];
+    let parameters = Parameters::new(2, Format::S32LE, 48_000.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Closest(Parameters::new(2, Format::S24LE, 48_000.0))
+    );
+}
+
+#[test]
+fn test_negotiate_rate_of_format() {
+    // The device supports 48 kHz, but not with the float format.
+    let mut capabilities = get_fake_capabilities();
+    let mut float = get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32);
+    float.rate_range = (44_100.0, 44_100.0);
+    let mut int16 = get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 16);
+    int16.rate_range = (48_000.0, 48_000.0);
+    capabilities.formats = vec![float, int16];
+    let parameters = Parameters::new(2, Format::F32LE, 48_000.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Closest(Parameters::new(2, Format::F32LE, 44_100.0))
+    );
+    let parameters = Parameters::new(2, Format::S16LE, 48_000.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Supported
+    );
+}
+
+#[test]
+fn test_negotiate_format_without_device_rate() {
+    // The float format has no rate the device supports, so the next closest
+    // format is chosen.
+    let mut capabilities = get_fake_capabilities();
+    let mut float = get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32);
+    float.rate_range = (96_000.0, 192_000.0);
+    let int16 = get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 16);
+    capabilities.formats = vec![float, int16];
+    let parameters = Parameters::new(2, Format::F32LE, 48_000.0);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Closest(Parameters::new(2, Format::S16LE, 48_000.0))
+    );
+}
+
+#[test]
+fn test_negotiate_without_matched_formats() {
+    let parameters = Parameters::new(2, Format::S16LE, 48_000.0);
+
+    // No format at all.
+    let mut capabilities = get_fake_capabilities();
+    capabilities.formats = vec![];
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Unsupported
+    );
+
+    // Only the formats unknown to `Format`.
+    capabilities.formats = vec![get_fake_format_range(
+        sys::kAudioFormatFlagIsSignedInteger,
+        20,
+    )];
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Unsupported
+    );
+
+    // No format with a rate the device supports.
+    let mut float = get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32);
+    float.rate_range = (96_000.0, 96_000.0);
+    capabilities.formats = vec![float];
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Unsupported
+    );
+}
+
+#[test]
+fn test_negotiate_without_rates_or_channels() {
+    let parameters = Parameters::new(2, Format::F32LE, 48_000.0);
+
+    let mut capabilities = get_fake_capabilities();
+    capabilities.rates = SupportedRates::new(vec![]);
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Unsupported
+    );
+
+    let mut capabilities = get_fake_capabilities();
+    capabilities.channels = 0;
+    assert_eq!(
+        negotiate_with_capabilities(&parameters, &capabilities),
+        Negotiation::Unsupported
+    );
+}
+
+// negotiate
+// ------------------------------------
+#[test]
+fn test_negotiate() {
+    use utils::get_default_device;
+    let scope = Scope::Output;
+    if let Ok(device) = get_default_device(&scope) {
+        let rate = device.get_rate(&scope).unwrap();
+        let parameters = Parameters::new(1, Format::F32LE, rate);
+        assert_ne!(
+            negotiate(&device, &scope, &parameters).unwrap(),
+            Negotiation::Unsupported
+        );
+    }
+}
+
+// Tests for Private Functions
+// ============================================================================
+
+// to_format
+// ------------------------------------
+#[test]
+fn test_to_format() {
+    let float = get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32);
+    assert_eq!(to_format(&float.format), Some(Format::F32LE));
+    let double = get_fake_format_range(sys::kAudioFormatFlagIsFloat, 64);
+    assert_eq!(to_format(&double.format), Some(Format::F64LE));
+    let uint8 = get_fake_format_range(0, 8);
+    assert_eq!(to_format(&uint8.format), Some(Format::U8));
+    let int16 = get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 16);
+    assert_eq!(to_format(&int16.format), Some(Format::S16LE));
+    let int24 = get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 24);
+    assert_eq!(to_format(&int24.format), Some(Format::S24LE));
+    let int32 = get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 32);
+    assert_eq!(to_format(&int32.format), Some(Format::S32LE));
+    let big_endian = get_fake_format_range(
+        sys::kAudioFormatFlagIsFloat | sys::kAudioFormatFlagIsBigEndian,
+        32,
+    );
+    assert_eq!(to_format(&big_endian.format), Some(Format::F32BE));
+}
+
+#[test]
+fn test_to_format_with_24_bit_in_32_bit() {
+    let mut int24_32 = get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 32);
+    int24_32.format.flags &= !sys::kAudioFormatFlagIsPacked;
+    int24_32.format.bits_per_channel = 24;
+    assert_eq!(to_format(&int24_32.format), Some(Format::S24_32LE));
+    int24_32.format.flags |= sys::kAudioFormatFlagIsBigEndian;
+    assert_eq!(to_format(&int24_32.format), Some(Format::S24_32BE));
+    int24_32.format.flags |= sys::kLinearPCMFormatFlagIsAlignedHigh;
+    assert_eq!(to_format(&int24_32.format), None);
+}
+
+#[test]
+fn test_to_format_unsupported() {
+    let int20 = get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 20);
+    assert_eq!(to_format(&int20.format), None);
+    let mut not_pcm = get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32);
+    not_pcm.format.format_id = sys::kAudioFormatAC3;
+    assert_eq!(to_format(&not_pcm.format), None);
+}
+
+// get_nearest_rate
+// ------------------------------------
+#[test]
+fn test_get_nearest_rate() {
+    let mut capabilities = get_fake_capabilities();
+    capabilities.rates = SupportedRates::new(vec![RateRange::Continuous(8_000.0, 96_000.0)]);
+    let mut float = get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32);
+    float.rate_range = (32_000.0, 48_000.0);
+    capabilities.formats = vec![float];
+    // The rate is bounded by the rate range of the format.
+    assert_eq!(
+        get_nearest_rate(&Format::F32LE, 96_000.0, &capabilities),
+        Some(48_000.0)
+    );
+    assert_eq!(
+        get_nearest_rate(&Format::F32LE, 44_100.0, &capabilities),
+        Some(44_100.0)
+    );
+    assert_eq!(
+        get_nearest_rate(&Format::S16LE, 44_100.0, &capabilities),
+        None
+    );
+}
+
+// get_format_distance
+// ------------------------------------
+#[test]
+fn test_get_format_distance() {
+    let same = get_format_distance(&Format::S16LE, &Format::S16LE);
+    let other_endian = get_format_distance(&Format::S16LE, &Format::S16BE);
+    let wider = get_format_distance(&Format::S16LE, &Format::S24LE);
+    let other_kind = get_format_distance(&Format::S16LE, &Format::F32LE);
+    assert!(same < other_endian);
+    assert!(other_endian < wider);
+    assert!(wider < other_kind);
+    // The samples in 3 bytes are closer to the packed 24-bit samples.
+    assert!(
+        get_format_distance(&Format::S24LE, &Format::S24LE)
+            < get_format_distance(&Format::S24LE, &Format::S24_32LE)
+    );
+}
+
+// get_supported_formats
+// ------------------------------------
+#[test]
+fn test_get_supported_formats() {
+    let ranges = vec![
+        get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 16),
+        get_fake_format_range(sys::kAudioFormatFlagIsFloat, 32),
+        get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 16),
+        get_fake_format_range(sys::kAudioFormatFlagIsSignedInteger, 20),
+    ];
+    assert_eq!(
+        get_supported_formats(&ranges),
+        vec![Format::S16LE, Format::F32LE]
+    );
+}
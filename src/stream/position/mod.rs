@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use super::Timing;
+
+// Count the frames produced by the callback and rendered to the device. The
+// count follows the sample times of the buffers when they're valid, so the
+// frames skipped by the device are counted as well. The sample time may
+// restart when the stream is restarted, so the count is rebased after each
+// start.
+//
+// The count is shared with the audio thread, which updates it by its own
+// `PositionTracker`.
+pub struct Position {
+    frames: AtomicU64,
+    rebase: AtomicBool,
+}
+
+impl Position {
+    pub fn new() -> Self {
+        Position {
+            frames: AtomicU64::new(0),
+            rebase: AtomicBool::new(false),
+        }
+    }
+
+    pub fn get(&self) -> u64 {
+        self.frames.load(Ordering::SeqCst)
+    }
+
+    // Called before the stream starts.
+    pub fn rebase(&self) {
+        self.rebase.store(true, Ordering::SeqCst);
+    }
+}
+
+// The part of the position only touched on the audio thread.
+pub struct PositionTracker {
+    base: u64,
+    first_sample_time: Option<f64>,
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        PositionTracker {
+            base: 0,
+            first_sample_time: None,
+        }
+    }
+
+    // Called on the audio thread after `produced` frames are rendered into
+    // the buffers with the given timing.
+    pub fn update(&mut self, position: &Position, timing: &Timing, produced: usize) {
+        if position.rebase.swap(false, Ordering::SeqCst) {
+            self.base = position.get();
+            self.first_sample_time = None;
+        }
+        let frames = if timing.has_sample_time() {
+            let first = *self.first_sample_time.get_or_insert(timing.sample_time);
+            let elapsed = (timing.sample_time - first).max(0.0) as u64;
+            self.base + elapsed + produced as u64
+        } else {
+            position.get() + produced as u64
+        };
+        // The position never goes back.
+        position.frames.fetch_max(frames, Ordering::SeqCst);
+    }
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
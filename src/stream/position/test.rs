@@ -0,0 +1,77 @@
+extern crate coreaudio_sys as sys;
+
+use super::*;
+
+fn get_timing(sample_time: Option<f64>) -> Timing {
+    match sample_time {
+        Some(sample_time) => Timing {
+            sample_time,
+            flags: sys::kAudioTimeStampSampleTimeValid,
+            ..Timing::default()
+        },
+        None => Timing::default(),
+    }
+}
+
+// Tests for Public Functions
+// ============================================================================
+
+// Position::new
+// ------------------------------------
+#[test]
+fn test_new() {
+    assert_eq!(Position::new().get(), 0);
+}
+
+// PositionTracker::update
+// ------------------------------------
+#[test]
+fn test_update_with_sample_time() {
+    let position = Position::new();
+    let mut tracker = PositionTracker::new();
+    tracker.update(&position, &get_timing(Some(1000.0)), 512);
+    assert_eq!(position.get(), 512);
+    tracker.update(&position, &get_timing(Some(1512.0)), 512);
+    assert_eq!(position.get(), 1024);
+    // The skipped frames are counted.
+    tracker.update(&position, &get_timing(Some(3000.0)), 512);
+    assert_eq!(position.get(), 2512);
+    // The partial buffer at the end of the stream.
+    tracker.update(&position, &get_timing(Some(3512.0)), 100);
+    assert_eq!(position.get(), 2612);
+}
+
+#[test]
+fn test_update_without_sample_time() {
+    let position = Position::new();
+    let mut tracker = PositionTracker::new();
+    tracker.update(&position, &get_timing(None), 512);
+    tracker.update(&position, &get_timing(None), 256);
+    assert_eq!(position.get(), 768);
+}
+
+#[test]
+fn test_update_never_goes_back() {
+    let position = Position::new();
+    let mut tracker = PositionTracker::new();
+    tracker.update(&position, &get_timing(Some(0.0)), 512);
+    tracker.update(&position, &get_timing(Some(512.0)), 0);
+    assert_eq!(position.get(), 512);
+}
+
+// Position::rebase
+// ------------------------------------
+#[test]
+fn test_rebase() {
+    let position = Position::new();
+    let mut tracker = PositionTracker::new();
+    tracker.update(&position, &get_timing(Some(5000.0)), 512);
+    tracker.update(&position, &get_timing(Some(5512.0)), 512);
+    assert_eq!(position.get(), 1024);
+    // The sample time restarts after the stream restarts.
+    position.rebase();
+    tracker.update(&position, &get_timing(Some(0.0)), 512);
+    assert_eq!(position.get(), 1536);
+    tracker.update(&position, &get_timing(Some(512.0)), 512);
+    assert_eq!(position.get(), 2048);
+}
@@ -0,0 +1,248 @@
+use super::Format;
+
+// Sample
+// ============================================================================
+// The types that can be used as the samples in the stream callbacks. Each type
+// maps to exactly one `Format`, so the format of a stream is derived from its
+// sample type and a mismatch is a compile error. The trait is sealed and only
+// implemented by the sample types listed in `Format`.
+//
+// The conversions map the full range of the integer samples to [-1.0, 1.0).
+// The values out of the range are saturated when converting to integers.
+pub trait Sample: private::Sealed + Copy + Send + 'static {
+    const FORMAT: Format;
+    const SILENCE: Self;
+
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+// I24
+// ============================================================================
+// A signed 24-bit sample packed in 3 bytes, which is the sample type of
+// `Format::S24LE`. The bytes are in little-endian order. Use `BigEndian<I24>`
+// for `Format::S24BE`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct I24([u8; 3]);
+
+impl I24 {
+    pub const MAX: i32 = 0x7F_FFFF;
+    pub const MIN: i32 = -0x80_0000;
+
+    // The value out of the 24-bit range is saturated.
+    pub fn new(value: i32) -> Self {
+        let value = value.clamp(Self::MIN, Self::MAX);
+        I24([value as u8, (value >> 8) as u8, (value >> 16) as u8])
+    }
+
+    pub fn from_bytes(bytes: [u8; 3]) -> Self {
+        I24(bytes)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 3] {
+        self.0
+    }
+
+    pub fn to_i32(&self) -> i32 {
+        // Put the 24 bits at the top then shift them back to extend the sign.
+        let value = (self.0[0] as i32) << 8 | (self.0[1] as i32) << 16 | (self.0[2] as i32) << 24;
+        value >> 8
+    }
+
+    // Reverse the byte order to convert between the little-endian and
+    // big-endian samples.
+    pub fn swap_bytes(&self) -> Self {
+        I24([self.0[2], self.0[1], self.0[0]])
+    }
+}
+
+impl From<I24> for i32 {
+    fn from(sample: I24) -> Self {
+        sample.to_i32()
+    }
+}
+
+// I24In32
+// ============================================================================
+// A signed 24-bit sample in the low 3 bytes of a 32-bit container, which is
+// the sample type of `Format::S24_32LE`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct I24In32(i32);
+
+impl I24In32 {
+    // The value out of the 24-bit range is saturated.
+    pub fn new(value: i32) -> Self {
+        I24In32(value.clamp(I24::MIN, I24::MAX))
+    }
+
+    pub fn to_i32(&self) -> i32 {
+        self.0
+    }
+
+    fn swap_bytes(&self) -> Self {
+        I24In32(self.0.swap_bytes())
+    }
+}
+
+impl From<I24In32> for i32 {
+    fn from(sample: I24In32) -> Self {
+        sample.to_i32()
+    }
+}
+
+// BigEndian
+// ============================================================================
+// A sample stored in big-endian byte order. `new` and `get` convert it from
+// and to the native value.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BigEndian<T>(T);
+
+// Implementations
+// ============================================================================
+macro_rules! impl_integer_sample {
+    ($type:ty, $format:expr, $silence:expr, $to_i64:expr, $from_i64:expr, $bits:expr) => {
+        impl private::Sealed for $type {}
+
+        impl Sample for $type {
+            const FORMAT: Format = $format;
+            const SILENCE: Self = $silence;
+
+            fn from_f64(value: f64) -> Self {
+                let scale = (1_i64 << ($bits - 1)) as f64;
+                let max = (1_i64 << ($bits - 1)) - 1;
+                let min = -(1_i64 << ($bits - 1));
+                let value = (value * scale).round().clamp(min as f64, max as f64) as i64;
+                $from_i64(value)
+            }
+
+            fn to_f64(self) -> f64 {
+                let scale = (1_i64 << ($bits - 1)) as f64;
+                $to_i64(self) as f64 / scale
+            }
+        }
+    };
+}
+
+macro_rules! impl_float_sample {
+    ($type:ty, $format:expr) => {
+        impl private::Sealed for $type {}
+
+        impl Sample for $type {
+            const FORMAT: Format = $format;
+            const SILENCE: Self = 0.0;
+
+            fn from_f64(value: f64) -> Self {
+                value as $type
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    };
+}
+
+macro_rules! impl_big_endian_sample {
+    ($type:ty, $format:expr, $swap:expr) => {
+        impl BigEndian<$type> {
+            pub fn new(value: $type) -> Self {
+                BigEndian($swap(value))
+            }
+
+            pub fn get(&self) -> $type {
+                $swap(self.0)
+            }
+        }
+
+        impl private::Sealed for BigEndian<$type> {}
+
+        impl Sample for BigEndian<$type> {
+            const FORMAT: Format = $format;
+            // The silence of the signed samples is all-zero bytes.
+            const SILENCE: Self = BigEndian(<$type as Sample>::SILENCE);
+
+            fn from_f64(value: f64) -> Self {
+                Self::new(<$type>::from_f64(value))
+            }
+
+            fn to_f64(self) -> f64 {
+                self.get().to_f64()
+            }
+        }
+    };
+}
+
+// The silence of the unsigned 8-bit samples is in the middle of the range.
+impl private::Sealed for u8 {}
+
+impl Sample for u8 {
+    const FORMAT: Format = Format::U8;
+    const SILENCE: Self = 128;
+
+    fn from_f64(value: f64) -> Self {
+        ((value * 128.0).round().clamp(-128.0, 127.0) + 128.0) as u8
+    }
+
+    fn to_f64(self) -> f64 {
+        (self as f64 - 128.0) / 128.0
+    }
+}
+
+impl_integer_sample!(
+    i16,
+    Format::S16LE,
+    0,
+    |sample: i16| sample as i64,
+    |value: i64| value as i16,
+    16
+);
+impl_integer_sample!(
+    I24,
+    Format::S24LE,
+    I24([0; 3]),
+    |sample: I24| sample.to_i32() as i64,
+    |value: i64| I24::new(value as i32),
+    24
+);
+impl_integer_sample!(
+    I24In32,
+    Format::S24_32LE,
+    I24In32(0),
+    |sample: I24In32| sample.to_i32() as i64,
+    |value: i64| I24In32::new(value as i32),
+    24
+);
+impl_integer_sample!(
+    i32,
+    Format::S32LE,
+    0,
+    |sample: i32| sample as i64,
+    |value: i64| value as i32,
+    32
+);
+impl_float_sample!(f32, Format::F32LE);
+impl_float_sample!(f64, Format::F64LE);
+
+impl_big_endian_sample!(i16, Format::S16BE, |value: i16| value.swap_bytes());
+impl_big_endian_sample!(I24, Format::S24BE, |value: I24| value.swap_bytes());
+impl_big_endian_sample!(I24In32, Format::S24_32BE, |value: I24In32| value
+    .swap_bytes());
+impl_big_endian_sample!(i32, Format::S32BE, |value: i32| value.swap_bytes());
+impl_big_endian_sample!(f32, Format::F32BE, |value: f32| f32::from_bits(
+    value.to_bits().swap_bytes()
+));
+impl_big_endian_sample!(f64, Format::F64BE, |value: f64| f64::from_bits(
+    value.to_bits().swap_bytes()
+));
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
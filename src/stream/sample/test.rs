@@ -0,0 +1,146 @@
+use super::*;
+use std::mem::size_of;
+
+// Tests for Public Functions
+// ============================================================================
+
+// I24
+// ------------------------------------
+#[test]
+fn test_i24_size() {
+    assert_eq!(size_of::<I24>(), 3);
+    assert_eq!(size_of::<[I24; 4]>(), 12);
+}
+
+// I24::new
+// I24::to_i32
+// ------------------------------------
+#[test]
+fn test_i24_to_i32() {
+    for value in &[0, 1, -1, 0x12_3456, -0x12_3456, I24::MAX, I24::MIN] {
+        assert_eq!(I24::new(*value).to_i32(), *value);
+        assert_eq!(i32::from(I24::new(*value)), *value);
+    }
+}
+
+#[test]
+fn test_i24_new_saturates() {
+    assert_eq!(I24::new(I24::MAX + 1).to_i32(), I24::MAX);
+    assert_eq!(I24::new(i32::max_value()).to_i32(), I24::MAX);
+    assert_eq!(I24::new(I24::MIN - 1).to_i32(), I24::MIN);
+    assert_eq!(I24::new(i32::min_value()).to_i32(), I24::MIN);
+}
+
+// I24::from_bytes
+// I24::to_bytes
+// ------------------------------------
+#[test]
+fn test_i24_bytes() {
+    let sample = I24::new(0x12_3456);
+    assert_eq!(sample.to_bytes(), [0x56, 0x34, 0x12]);
+    assert_eq!(I24::from_bytes([0x56, 0x34, 0x12]), sample);
+    assert_eq!(I24::from_bytes([0xFF, 0xFF, 0xFF]).to_i32(), -1);
+}
+
+// I24::swap_bytes
+// ------------------------------------
+#[test]
+fn test_i24_swap_bytes() {
+    let sample = I24::new(0x12_3456);
+    assert_eq!(sample.swap_bytes().to_bytes(), [0x12, 0x34, 0x56]);
+    assert_eq!(sample.swap_bytes().swap_bytes(), sample);
+}
+
+// I24In32
+// ------------------------------------
+#[test]
+fn test_i24_in_32() {
+    assert_eq!(size_of::<I24In32>(), 4);
+    assert_eq!(I24In32::new(0x12_3456).to_i32(), 0x12_3456);
+    assert_eq!(I24In32::new(I24::MAX + 1).to_i32(), I24::MAX);
+    assert_eq!(i32::from(I24In32::new(I24::MIN - 1)), I24::MIN);
+}
+
+// BigEndian
+// ------------------------------------
+#[test]
+fn test_big_endian() {
+    assert_eq!(size_of::<BigEndian<i16>>(), 2);
+    assert_eq!(size_of::<BigEndian<I24>>(), 3);
+    assert_eq!(BigEndian::<i16>::new(0x1234).get(), 0x1234);
+    assert_eq!(BigEndian::<i32>::new(-5).get(), -5);
+    assert_eq!(BigEndian::<f32>::new(0.25).get(), 0.25);
+    assert_eq!(BigEndian::<f64>::new(-0.5).get(), -0.5);
+    assert_eq!(BigEndian::<I24>::new(I24::new(-7)).get(), I24::new(-7));
+    assert_eq!(
+        BigEndian::<I24In32>::new(I24In32::new(7)).get(),
+        I24In32::new(7)
+    );
+}
+
+#[test]
+fn test_big_endian_bytes() {
+    let sample = BigEndian::<i16>::new(0x1234);
+    let bytes: [u8; 2] = unsafe { std::mem::transmute(sample) };
+    assert_eq!(bytes, [0x12, 0x34]);
+    let sample = BigEndian::<I24>::new(I24::new(0x12_3456));
+    let bytes: [u8; 3] = unsafe { std::mem::transmute(sample) };
+    assert_eq!(bytes, [0x12, 0x34, 0x56]);
+}
+
+// Sample::FORMAT
+// ------------------------------------
+#[test]
+fn test_sample_format() {
+    fn check<T: Sample>(format: Format) {
+        assert_eq!(T::FORMAT, format);
+        assert_eq!(T::FORMAT.byte_size(), size_of::<T>());
+    }
+    check::<u8>(Format::U8);
+    check::<i16>(Format::S16LE);
+    check::<I24>(Format::S24LE);
+    check::<I24In32>(Format::S24_32LE);
+    check::<i32>(Format::S32LE);
+    check::<f32>(Format::F32LE);
+    check::<f64>(Format::F64LE);
+    check::<BigEndian<i16>>(Format::S16BE);
+    check::<BigEndian<I24>>(Format::S24BE);
+    check::<BigEndian<I24In32>>(Format::S24_32BE);
+    check::<BigEndian<i32>>(Format::S32BE);
+    check::<BigEndian<f32>>(Format::F32BE);
+    check::<BigEndian<f64>>(Format::F64BE);
+}
+
+// Sample::SILENCE
+// ------------------------------------
+#[test]
+fn test_sample_silence() {
+    assert_eq!(u8::SILENCE, 128);
+    assert_eq!(u8::SILENCE.to_f64(), 0.0);
+    assert_eq!(i16::SILENCE.to_f64(), 0.0);
+    assert_eq!(I24::SILENCE.to_f64(), 0.0);
+    assert_eq!(BigEndian::<f32>::SILENCE.to_f64(), 0.0);
+}
+
+// Sample::from_f64
+// Sample::to_f64
+// ------------------------------------
+#[test]
+fn test_sample_conversions() {
+    assert_eq!(u8::from_f64(-1.0), 0);
+    assert_eq!(u8::from_f64(0.0), 128);
+    assert_eq!(u8::from_f64(1.0), 255);
+    assert_eq!(i16::from_f64(-1.0), i16::min_value());
+    assert_eq!(i16::from_f64(0.5), 16384);
+    assert_eq!(i16::from_f64(1.0), i16::max_value());
+    assert_eq!(i16::from_f64(2.0), i16::max_value());
+    assert_eq!(i16::min_value().to_f64(), -1.0);
+    assert_eq!(I24::from_f64(-1.0).to_i32(), I24::MIN);
+    assert_eq!(I24::from_f64(1.0).to_i32(), I24::MAX);
+    assert_eq!(I24In32::from_f64(0.5).to_i32(), 0x40_0000);
+    assert_eq!(i32::from_f64(-1.0), i32::min_value());
+    assert_eq!(i32::from_f64(1.0), i32::max_value());
+    assert_eq!(f32::from_f64(0.25), 0.25);
+    assert_eq!(BigEndian::<i16>::from_f64(0.5).get(), 16384);
+    assert_eq!(BigEndian::<i16>::from_f64(0.5).to_f64(), 0.5);
+}
@@ -0,0 +1,65 @@
+use std::ptr;
+
+// The table of the channel buffers passed to the audio callbacks. The table
+// is allocated once with a slot for each channel and refilled in every
+// callback, so no allocation happens on the audio thread. The slices are only
+// in the table during the call.
+pub struct SliceTable<T> {
+    slices: Vec<*mut [T]>,
+}
+
+impl<T> SliceTable<T> {
+    pub fn new(channels: usize) -> Self {
+        SliceTable {
+            slices: Vec::with_capacity(channels),
+        }
+    }
+
+    // Call `f` with the slices of `len` samples starting at the given
+    // pointers. The pointers beyond the channels of the table are ignored.
+    //
+    // The pointers must point to `len` valid samples and must not overlap.
+    pub unsafe fn with_slices<I, F, R>(&mut self, pointers: I, len: usize, f: F) -> R
+    where
+        I: IntoIterator<Item = *mut T>,
+        F: for<'a> FnOnce(&'a mut [&'a mut [T]]) -> R,
+    {
+        self.fill(pointers, len);
+        // The raw slices have the layout of the references. The references
+        // are dropped with the table cleared after the call.
+        let table = &mut *(self.slices.as_mut_slice() as *mut [*mut [T]] as *mut [&mut [T]]);
+        let result = f(table);
+        self.slices.clear();
+        result
+    }
+
+    // Same as `with_slices` but the slices are read only.
+    pub unsafe fn with_read_only_slices<I, F, R>(&mut self, pointers: I, len: usize, f: F) -> R
+    where
+        I: IntoIterator<Item = *mut T>,
+        F: for<'a> FnOnce(&'a [&'a [T]]) -> R,
+    {
+        self.fill(pointers, len);
+        let table = &*(self.slices.as_slice() as *const [*mut [T]] as *const [&[T]]);
+        let result = f(table);
+        self.slices.clear();
+        result
+    }
+
+    unsafe fn fill<I>(&mut self, pointers: I, len: usize)
+    where
+        I: IntoIterator<Item = *mut T>,
+    {
+        self.slices.clear();
+        let channels = self.slices.capacity();
+        for pointer in pointers.into_iter().take(channels) {
+            self.slices
+                .push(ptr::slice_from_raw_parts_mut(pointer, len));
+        }
+    }
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
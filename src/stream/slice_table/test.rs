@@ -0,0 +1,65 @@
+use super::*;
+
+// Tests for Public Functions
+// ============================================================================
+
+// SliceTable::with_slices
+// ------------------------------------
+#[test]
+fn test_with_slices() {
+    let mut left = [0.0_f32; 4];
+    let mut right = [0.0_f32; 4];
+    let mut table = SliceTable::new(2);
+    let pointers = [left.as_mut_ptr(), right.as_mut_ptr()];
+    let channels = unsafe {
+        table.with_slices(pointers.iter().cloned(), 3, |buffers| {
+            for (channel, buffer) in buffers.iter_mut().enumerate() {
+                assert_eq!(buffer.len(), 3);
+                for sample in buffer.iter_mut() {
+                    *sample = channel as f32 + 1.0;
+                }
+            }
+            buffers.len()
+        })
+    };
+    assert_eq!(channels, 2);
+    assert_eq!(left, [1.0, 1.0, 1.0, 0.0]);
+    assert_eq!(right, [2.0, 2.0, 2.0, 0.0]);
+    assert!(table.slices.is_empty());
+}
+
+#[test]
+fn test_with_slices_without_allocation() {
+    let mut data = [[0_i16; 8]; 3];
+    let mut table = SliceTable::new(2);
+    let storage = table.slices.as_ptr();
+    for _ in 0..3 {
+        // The pointers beyond the channels are ignored.
+        let pointers = data.iter_mut().map(|channel| channel.as_mut_ptr());
+        let channels = unsafe { table.with_slices(pointers, 8, |buffers| buffers.len()) };
+        assert_eq!(channels, 2);
+        assert_eq!(table.slices.as_ptr(), storage);
+        assert_eq!(table.slices.capacity(), 2);
+    }
+}
+
+// SliceTable::with_read_only_slices
+// ------------------------------------
+#[test]
+fn test_with_read_only_slices() {
+    let mut left = [1_u8, 2, 3];
+    let mut right = [4_u8, 5, 6];
+    let mut table = SliceTable::new(2);
+    let pointers = [left.as_mut_ptr(), right.as_mut_ptr()];
+    let sum: u32 = unsafe {
+        table.with_read_only_slices(pointers.iter().cloned(), 2, |buffers| {
+            buffers
+                .iter()
+                .flat_map(|buffer| buffer.iter())
+                .map(|&sample| u32::from(sample))
+                .sum()
+        })
+    };
+    assert_eq!(sum, 1 + 2 + 4 + 5);
+    assert!(table.slices.is_empty());
+}
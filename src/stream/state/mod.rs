@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// The life cycle of a `Stream`:
+//
+//   Initialized --start--> Started --stop--> Stopped --start--> Started
+//                             |
+//                             +--(end of stream)--> Drained --stop--> Stopped
+//                             +--(render error)---> Error
+//
+//   (any state except Error) --(device lost)--> DeviceLost
+//
+// A stream is `Initialized` once it's created, so it can neither be started
+// before being initialized nor be initialized twice. The `Drained` and
+// `Error` states are entered on the audio thread, and `DeviceLost` on the HAL
+// notification thread. A stream in `Error` or `DeviceLost` can't be started
+// again.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum State {
+    Initialized,
+    Started,
+    Stopped,
+    Drained,
+    Error,
+    DeviceLost,
+}
+
+impl State {
+    // Whether the stream is in one of the states it can't leave.
+    pub fn is_error(self) -> bool {
+        self == State::Error || self == State::DeviceLost
+    }
+
+    fn to_value(self) -> usize {
+        match self {
+            State::Initialized => 0,
+            State::Started => 1,
+            State::Stopped => 2,
+            State::Drained => 3,
+            State::Error => 4,
+            State::DeviceLost => 5,
+        }
+    }
+
+    fn from_value(value: usize) -> Self {
+        match value {
+            0 => State::Initialized,
+            1 => State::Started,
+            2 => State::Stopped,
+            3 => State::Drained,
+            4 => State::Error,
+            5 => State::DeviceLost,
+            v => panic!("Invalid state value: {}", v),
+        }
+    }
+}
+
+// The state shared by the audio thread and the thread controlling the stream.
+pub struct AtomicState(AtomicUsize);
+
+impl AtomicState {
+    pub fn new(state: State) -> Self {
+        AtomicState(AtomicUsize::new(state.to_value()))
+    }
+
+    pub fn get(&self) -> State {
+        State::from_value(self.0.load(Ordering::SeqCst))
+    }
+
+    pub fn set(&self, state: State) {
+        self.0.store(state.to_value(), Ordering::SeqCst);
+    }
+
+    // Change to the `to` state if the current state is one of the `from`
+    // states. Return the previous state if it's changed, or the current state
+    // otherwise.
+    pub fn transit(&self, from: &[State], to: State) -> Result<State, State> {
+        let mut current = self.get();
+        loop {
+            if !from.contains(&current) {
+                return Err(current);
+            }
+            match self.0.compare_exchange(
+                current.to_value(),
+                to.to_value(),
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return Ok(current),
+                Err(value) => current = State::from_value(value),
+            }
+        }
+    }
+}
+
+// The states waiting to be notified, in order. They're pushed on the audio
+// thread when the notification can't be done without blocking, and popped by
+// the thread holding the state callback, so there is only one thread pushing
+// and one thread popping at a time. The newest states are dropped if the
+// queue is full, which shouldn't happen since the audio thread only enters a
+// few states between the notifications.
+pub struct PendingStates {
+    states: [AtomicUsize; PENDING_STATES],
+    // The number of the states pushed and popped so far.
+    pushed: AtomicUsize,
+    popped: AtomicUsize,
+}
+
+impl PendingStates {
+    pub fn new() -> Self {
+        PendingStates {
+            states: Default::default(),
+            pushed: AtomicUsize::new(0),
+            popped: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn push(&self, state: State) {
+        let pushed = self.pushed.load(Ordering::SeqCst);
+        if pushed.wrapping_sub(self.popped.load(Ordering::SeqCst)) == PENDING_STATES {
+            return;
+        }
+        self.states[pushed % PENDING_STATES].store(state.to_value(), Ordering::SeqCst);
+        self.pushed.store(pushed.wrapping_add(1), Ordering::SeqCst);
+    }
+
+    pub fn pop(&self) -> Option<State> {
+        let popped = self.popped.load(Ordering::SeqCst);
+        if popped == self.pushed.load(Ordering::SeqCst) {
+            return None;
+        }
+        let value = self.states[popped % PENDING_STATES].load(Ordering::SeqCst);
+        self.popped.store(popped.wrapping_add(1), Ordering::SeqCst);
+        Some(State::from_value(value))
+    }
+}
+
+const PENDING_STATES: usize = 8;
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
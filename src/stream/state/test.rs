@@ -0,0 +1,118 @@
+use super::*;
+
+// Tests for Public Functions
+// ============================================================================
+
+// AtomicState::new
+// AtomicState::get
+// ------------------------------------
+#[test]
+fn test_new() {
+    for state in &[
+        State::Initialized,
+        State::Started,
+        State::Stopped,
+        State::Drained,
+        State::Error,
+        State::DeviceLost,
+    ] {
+        assert_eq!(AtomicState::new(*state).get(), *state);
+    }
+}
+
+// State::is_error
+// ------------------------------------
+#[test]
+fn test_is_error() {
+    assert!(!State::Initialized.is_error());
+    assert!(!State::Started.is_error());
+    assert!(!State::Stopped.is_error());
+    assert!(!State::Drained.is_error());
+    assert!(State::Error.is_error());
+    assert!(State::DeviceLost.is_error());
+}
+
+// AtomicState::transit
+// ------------------------------------
+#[test]
+fn test_transit() {
+    let state = AtomicState::new(State::Initialized);
+    assert_eq!(
+        state.transit(&[State::Initialized, State::Stopped], State::Started),
+        Ok(State::Initialized)
+    );
+    assert_eq!(state.get(), State::Started);
+    assert_eq!(
+        state.transit(&[State::Started, State::Drained], State::Stopped),
+        Ok(State::Started)
+    );
+    assert_eq!(state.get(), State::Stopped);
+}
+
+#[test]
+fn test_transit_from_invalid_state() {
+    let state = AtomicState::new(State::Stopped);
+    assert_eq!(
+        state.transit(&[State::Started], State::Drained),
+        Err(State::Stopped)
+    );
+    assert_eq!(state.get(), State::Stopped);
+}
+
+#[test]
+fn test_transit_concurrently() {
+    use std::sync::Arc;
+    use std::thread;
+
+    // Only one of the threads can change the state.
+    let state = Arc::new(AtomicState::new(State::Started));
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let state = state.clone();
+            let to = if i % 2 == 0 {
+                State::Drained
+            } else {
+                State::Stopped
+            };
+            thread::spawn(move || state.transit(&[State::Started], to).is_ok())
+        })
+        .collect();
+    let changed = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(|changed| *changed)
+        .count();
+    assert_eq!(changed, 1);
+    assert_ne!(state.get(), State::Started);
+}
+
+// PendingStates
+// ------------------------------------
+#[test]
+fn test_pending_states() {
+    let pending = PendingStates::new();
+    assert_eq!(pending.pop(), None);
+    pending.push(State::Drained);
+    pending.push(State::Error);
+    // All the states are kept in order.
+    assert_eq!(pending.pop(), Some(State::Drained));
+    assert_eq!(pending.pop(), Some(State::Error));
+    assert_eq!(pending.pop(), None);
+}
+
+#[test]
+fn test_pending_states_when_full() {
+    let pending = PendingStates::new();
+    for _ in 0..PENDING_STATES {
+        pending.push(State::Drained);
+    }
+    // The newest state is dropped.
+    pending.push(State::Error);
+    for _ in 0..PENDING_STATES {
+        assert_eq!(pending.pop(), Some(State::Drained));
+    }
+    assert_eq!(pending.pop(), None);
+    // The queue is usable again after it's emptied.
+    pending.push(State::Error);
+    assert_eq!(pending.pop(), Some(State::Error));
+}
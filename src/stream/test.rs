@@ -0,0 +1,790 @@
+use super::*;
+use utils::get_devices;
+
+fn callback_f32(buffers: CallbackArgs<f32>, _timing: &Timing) -> usize {
+    buffers[0].len()
+}
+
+// The AUHAL unit applies the volume, so the volume is seen by the unit.
+fn get_unit_volume<T: Sample>(stm: &Stream<T>) -> f32 {
+    stm.context
+        .unit
+        .get_parameter(
+            sys::kHALOutputParam_Volume,
+            sys::kAudioUnitScope_Global,
+            Element::Output,
+        )
+        .unwrap()
+}
+
+fn is_unit_running<T: Sample>(stm: &Stream<T>) -> bool {
+    let running: u32 = stm
+        .context
+        .unit
+        .get_property(
+            sys::kAudioOutputUnitProperty_IsRunning,
+            sys::kAudioUnitScope_Global,
+            Element::Output,
+        )
+        .unwrap();
+    running != 0
+}
+
+// Tests for Public Functions
+// ============================================================================
+
+// Stream::new
+// ------------------------------------
+#[test]
+fn test_stream_with_stateful_closure() {
+    use std::sync::mpsc;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut frames = 0;
+    let stm = Stream::<f32>::new(2, 44_100.0, move |buffers, _timing| {
+        for buffer in buffers.iter_mut() {
+            for data in buffer.iter_mut() {
+                *data = 0.0;
+            }
+        }
+        frames += buffers[0].len();
+        let _ = sender.send(frames);
+        buffers[0].len()
+    })
+    .unwrap();
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    stm.stop().unwrap();
+    // The frames are accumulated in the state owned by the closure, across
+    // the calls.
+    let counts: Vec<usize> = receiver.try_iter().collect();
+    assert!(counts.len() > 1);
+    assert!(counts.windows(2).all(|pair| pair[0] < pair[1]));
+    // All the accumulated frames are rendered.
+    assert!(stm.position() >= *counts.last().unwrap() as u64);
+}
+
+#[test]
+fn test_stream_timing() {
+    use std::sync::mpsc;
+
+    let (sender, receiver) = mpsc::channel();
+    let stm = Stream::<f32>::new(1, 44_100.0, move |buffers, timing| {
+        let _ = sender.send((timing.clone(), buffers[0].len()));
+        buffers[0].len()
+    })
+    .unwrap();
+    let start = get_current_host_time();
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    stm.stop().unwrap();
+    let received: Vec<(Timing, usize)> = receiver.try_iter().collect();
+    for &(ref timing, _) in received.iter() {
+        assert!(timing.has_sample_time());
+        assert!(timing.has_host_time());
+        assert!(timing.host_time >= start);
+    }
+    // The sample time of the next buffer follows the frames in this buffer.
+    for pair in received.windows(2) {
+        let (ref timing, frames) = pair[0];
+        assert_eq!(timing.sample_time + frames as f64, pair[1].0.sample_time);
+    }
+}
+
+// Stream::is_drained
+// Stream::set_drained_callback
+// ------------------------------------
+#[test]
+fn test_stream_drained() {
+    use std::sync::mpsc;
+
+    // Play 10 ms of sound then end the stream.
+    let mut remaining = 441;
+    let (sender, receiver) = mpsc::channel();
+    let stm = Stream::<f32>::new(1, 44_100.0, move |buffers, _timing| {
+        let frames = remaining.min(buffers[0].len());
+        for data in buffers[0][..frames].iter_mut() {
+            *data = 0.1;
+        }
+        remaining -= frames;
+        frames
+    })
+    .unwrap();
+    stm.set_drained_callback(
+        move || {
+            let _ = sender.send(());
+        },
+        true,
+    );
+    assert!(!stm.is_drained());
+    stm.start().unwrap();
+    receiver
+        .recv_timeout(std::time::Duration::from_secs(1))
+        .unwrap();
+    assert!(stm.is_drained());
+    // The unit is stopped off the audio thread after the notification.
+    let now = std::time::Instant::now();
+    while is_unit_running(&stm) && now.elapsed() < std::time::Duration::from_secs(1) {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(!is_unit_running(&stm));
+    assert_eq!(stm.get_state(), State::Drained);
+    stm.stop().unwrap();
+}
+
+#[test]
+fn test_stream_set_drained_callback_after_drained() {
+    use std::sync::mpsc;
+
+    let stm = Stream::<f32>::new(1, 44_100.0, |_buffers, _timing| 0).unwrap();
+    stm.start().unwrap();
+    let now = std::time::Instant::now();
+    while !stm.is_drained() && now.elapsed() < std::time::Duration::from_secs(1) {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    stm.stop().unwrap();
+    assert!(stm.is_drained());
+    let (sender, receiver) = mpsc::channel();
+    stm.set_drained_callback(
+        move || {
+            let _ = sender.send(());
+        },
+        false,
+    );
+    assert!(receiver.try_recv().is_ok());
+}
+
+#[test]
+fn test_stream_restart_after_drained() {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+
+    // The callback ends the stream every time it's called.
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let stm = Stream::<f32>::new(1, 44_100.0, move |_buffers, _timing| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        0
+    })
+    .unwrap();
+    for _ in 0..2 {
+        stm.start().unwrap();
+        let now = std::time::Instant::now();
+        while !stm.is_drained() && now.elapsed() < std::time::Duration::from_secs(1) {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(stm.is_drained());
+        assert_eq!(stm.get_state(), State::Drained);
+        stm.stop().unwrap();
+    }
+    // The callback is called again after the restart.
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+// Stream::start
+// Stream::stop
+// Stream::get_state
+// ------------------------------------
+#[test]
+fn test_stream_state_transitions() {
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    assert_eq!(stm.get_state(), State::Initialized);
+    match stm.stop() {
+        Err(Error::InvalidState(State::Initialized)) => {}
+        _ => panic!("Stopping a stream before starting should fail"),
+    }
+    stm.start().unwrap();
+    assert_eq!(stm.get_state(), State::Started);
+    match stm.start() {
+        Err(Error::InvalidState(State::Started)) => {}
+        _ => panic!("Starting a started stream should fail"),
+    }
+    stm.stop().unwrap();
+    assert_eq!(stm.get_state(), State::Stopped);
+    match stm.stop() {
+        Err(Error::InvalidState(State::Stopped)) => {}
+        _ => panic!("Stopping a stopped stream should fail"),
+    }
+    // A stopped stream can be started again.
+    stm.start().unwrap();
+    stm.stop().unwrap();
+}
+
+#[test]
+fn test_stream_with_lost_device() {
+    use std::sync::mpsc;
+
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    stm.set_state_callback(move |state| {
+        let _ = sender.send(state);
+    });
+    stm.start().unwrap();
+    // Pretend the device is lost.
+    stm.context.on_device_lost();
+    assert_eq!(stm.get_state(), State::DeviceLost);
+    let states: Vec<State> = receiver.try_iter().collect();
+    assert_eq!(states, vec![State::Started, State::DeviceLost]);
+    match stm.stop() {
+        Err(Error::DeviceLost) => {}
+        _ => panic!("Stopping a stream with a lost device should fail"),
+    }
+    match stm.start() {
+        Err(Error::DeviceLost) => {}
+        _ => panic!("Starting a stream with a lost device should fail"),
+    }
+}
+
+#[test]
+fn test_drop_stream_with_lost_device() {
+    let mut stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    stm.start().unwrap();
+    stm.context.on_device_lost();
+    // The unit of a lost device fails to stop. Replace the unit by a unit
+    // failing every operation to mimic it.
+    let unit = std::mem::replace(&mut stm.context.unit, AudioUnit::new_invalid());
+    drop(unit);
+    assert!(stm.context.unit.stop().is_err());
+    // Dropping the stream doesn't panic.
+    drop(stm);
+}
+
+#[test]
+fn test_drop_stream_in_error() {
+    let mut stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    stm.start().unwrap();
+    // Pretend the stream fails to switch to another device, so its unit
+    // fails every operation.
+    stm.context.state.set(State::Error);
+    let unit = std::mem::replace(&mut stm.context.unit, AudioUnit::new_invalid());
+    drop(unit);
+    // Dropping the stream doesn't panic.
+    drop(stm);
+}
+
+// Stream::set_state_callback
+// ------------------------------------
+#[test]
+fn test_stream_state_callback() {
+    use std::sync::mpsc;
+
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    stm.set_state_callback(move |state| {
+        let _ = sender.send(state);
+    });
+    stm.start().unwrap();
+    stm.stop().unwrap();
+    let states: Vec<State> = receiver.try_iter().collect();
+    assert_eq!(states, vec![State::Started, State::Stopped]);
+}
+
+#[test]
+fn test_stream_state_callback_with_drained_stream() {
+    use std::sync::mpsc;
+
+    let stm = Stream::<f32>::new(1, 44_100.0, |_buffers, _timing| 0).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    stm.set_state_callback(move |state| {
+        let _ = sender.send(state);
+    });
+    stm.start().unwrap();
+    let timeout = std::time::Duration::from_secs(1);
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), State::Started);
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), State::Drained);
+    assert_eq!(stm.get_state(), State::Drained);
+    stm.stop().unwrap();
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), State::Stopped);
+}
+
+// Stream::position
+// Stream::latency
+// ------------------------------------
+#[test]
+fn test_stream_position() {
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    assert_eq!(stm.position(), 0);
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    stm.stop().unwrap();
+    let position = stm.position();
+    assert!(position > 0);
+    // The position keeps counting after the stream restarts.
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    stm.stop().unwrap();
+    assert!(stm.position() > position);
+}
+
+#[test]
+fn test_stream_position_after_end_of_stream() {
+    // Play 10 ms of sound then end the stream.
+    let mut remaining = 441;
+    let stm = Stream::<f32>::new(1, 44_100.0, move |buffers, _timing| {
+        let frames = remaining.min(buffers[0].len());
+        remaining -= frames;
+        frames
+    })
+    .unwrap();
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    stm.stop().unwrap();
+    // The silence after the end of the stream isn't counted.
+    assert!(stm.position() >= 441);
+    assert!(stm.position() < 44_100 / 5);
+}
+
+#[test]
+fn test_stream_latency() {
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    let latency = stm.latency().unwrap();
+    // The latency of the device is part of the latency of the stream.
+    assert!(latency >= stm.context.latency.load(Ordering::SeqCst) as u64);
+    // The latency is in the frames of the stream, so it doubles with the
+    // rate. Each of the two latencies may be rounded up by one frame.
+    let stm = Stream::<f32>::new(2, 88_200.0, callback_f32).unwrap();
+    let doubled = stm.latency().unwrap();
+    assert!(doubled + 2 >= 2 * latency);
+    assert!(doubled <= 2 * latency + 2);
+}
+
+// Stream::volume
+// Stream::set_volume
+// ------------------------------------
+#[test]
+fn test_stream_volume() {
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    assert_eq!(stm.volume(), 1.0);
+    // The volume set before the stream starts is kept after it starts.
+    stm.set_volume(0.25);
+    stm.start().unwrap();
+    assert_eq!(get_unit_volume(&stm), 0.25);
+    stm.set_volume(0.5);
+    assert_eq!(stm.volume(), 0.5);
+    assert_eq!(get_unit_volume(&stm), 0.5);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    stm.set_volume(0.0);
+    assert_eq!(stm.volume(), 0.0);
+    assert_eq!(get_unit_volume(&stm), 0.0);
+    stm.stop().unwrap();
+}
+
+#[test]
+fn test_stream_set_invalid_volume() {
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    stm.set_volume(1.5);
+    assert_eq!(stm.volume(), 1.0);
+    stm.set_volume(-0.5);
+    assert_eq!(stm.volume(), 0.0);
+    stm.set_volume(0.5);
+    stm.set_volume(f32::NAN);
+    assert_eq!(stm.volume(), 0.0);
+    // The clamped volume is what's applied.
+    assert_eq!(get_unit_volume(&stm), 0.0);
+    stm.set_volume(1.5);
+    assert_eq!(get_unit_volume(&stm), 1.0);
+}
+
+#[test]
+fn test_stream_volume_by_gain() {
+    let mut stm = Stream::<f32>::new(1, 44_100.0, |buffers, _timing| {
+        for data in buffers[0].iter_mut() {
+            *data = 1.0;
+        }
+        buffers[0].len()
+    })
+    .unwrap();
+    // The unit failing every operation can't apply the volume, so the volume
+    // is applied to the samples instead.
+    let unit = std::mem::replace(&mut stm.context.unit, AudioUnit::new_invalid());
+    drop(unit);
+    stm.set_volume(0.5);
+    assert_eq!(stm.volume(), 0.5);
+
+    // Render the buffers longer than the ramp of the gain on this thread.
+    const FRAMES: usize = 1024;
+    let mut samples = vec![0.0_f32; FRAMES];
+    let mut buffers = [sys::AudioBuffer {
+        mNumberChannels: 1,
+        mDataByteSize: (FRAMES * size_of::<f32>()) as u32,
+        mData: samples.as_mut_ptr() as *mut c_void,
+    }];
+    let data = AudioData {
+        buffers: unsafe { slice::from_raw_parts_mut(buffers.as_mut_ptr(), 1) },
+        frames: FRAMES,
+        timing: Timing::default(),
+        data_type: PhantomData,
+    };
+    let render_state = unsafe { &mut *stm.context.render_state.get() };
+    stm.context.get_buffer_data(render_state, data);
+    // The gain moves to the volume without a jump.
+    assert!(samples[0] < 1.0 && samples[0] > 0.5);
+    assert!(samples.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert_eq!(samples[FRAMES - 1], 0.5);
+}
+
+// Stream::set_device_changed_callback
+// ------------------------------------
+#[test]
+fn test_stream_on_default_device() {
+    use std::sync::mpsc;
+
+    let default_device = utils::get_default_device(&Scope::Output).unwrap();
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    stm.set_state_callback(move |state| {
+        let _ = sender.send(state);
+    });
+    // The stream plays on the default device when it's created.
+    assert_eq!(*stm.context.route.lock().unwrap(), default_device);
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(stm.get_state(), State::Started);
+    assert!(stm.position() > 0);
+    stm.stop().unwrap();
+    let states: Vec<State> = receiver.try_iter().collect();
+    assert_eq!(states, vec![State::Started, State::Stopped]);
+}
+
+#[test]
+fn test_stream_set_device_changed_callback_with_device() {
+    if let Ok(device) = utils::get_default_device(&Scope::Output) {
+        let stm = Stream::with_device(&device, 2, 44_100.0, callback_f32).unwrap();
+        match stm.set_device_changed_callback(|_device| {}) {
+            Err(Error::FixedDevice) => {}
+            _ => panic!("A stream with a device shouldn't switch to another device"),
+        }
+    }
+}
+
+// Get another output device and make it the default device. Return the
+// previous default device and the new one.
+fn change_default_device() -> Option<(AudioObject, AudioObject)> {
+    let default_device = utils::get_default_device(&Scope::Output).unwrap();
+    let devices = get_devices(&Scope::Output).unwrap();
+    // Need at least two output devices.
+    let device = devices
+        .into_iter()
+        .find(|device| device != &default_device)?;
+    utils::set_default_device(&device, &Scope::Output).unwrap();
+    Some((default_device, device))
+}
+
+// Changing the default device interrupts the other processes using it.
+#[test]
+#[ignore]
+fn test_stream_follow_changed_default_device() {
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    let position = stm.position();
+    let (default_device, device) = match change_default_device() {
+        Some(devices) => devices,
+        None => return,
+    };
+    // The plain stream moves to the new default device by itself.
+    for _ in 0..100 {
+        if *stm.context.route.lock().unwrap() == device {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let route = stm.context.route.lock().unwrap().clone();
+    let state = stm.get_state();
+    let played = stm.position() > position;
+    stm.stop().unwrap();
+    utils::set_default_device(&default_device, &Scope::Output).unwrap();
+    assert_eq!(route, device);
+    assert_eq!(state, State::Started);
+    assert!(played);
+}
+
+#[test]
+#[ignore]
+fn test_stream_device_changed_callback() {
+    use std::sync::mpsc;
+
+    let (sender, receiver) = mpsc::channel();
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    stm.set_device_changed_callback(move |device| {
+        let _ = sender.send(device.clone());
+    })
+    .unwrap();
+    stm.set_volume(0.5);
+    stm.start().unwrap();
+    let position = stm.position();
+    let (default_device, device) = match change_default_device() {
+        Some(devices) => devices,
+        None => return,
+    };
+    let changed = receiver.recv_timeout(std::time::Duration::from_secs(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(stm.get_state(), State::Started);
+    assert_eq!(stm.volume(), 0.5);
+    assert!(stm.position() > position);
+    stm.stop().unwrap();
+    utils::set_default_device(&default_device, &Scope::Output).unwrap();
+    assert_eq!(changed.unwrap(), device);
+}
+
+// Stream::with_device
+// ------------------------------------
+#[test]
+fn test_stream_with_input_only_device() {
+    let outputs = get_devices(&Scope::Output).unwrap_or_default();
+    let input_only = get_devices(&Scope::Input)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|device| !outputs.contains(device));
+    if let Some(device) = input_only {
+        match Stream::with_device(&device, 2, 44_100.0, callback_f32) {
+            Err(Error::WrongScope) => {}
+            _ => panic!("The stream on an input-only device should fail"),
+        }
+    }
+}
+
+#[test]
+fn test_stream_with_device() {
+    if let Ok(device) = utils::get_default_device(&Scope::Output) {
+        let stm = Stream::with_device(&device, 2, 44_100.0, callback_f32).unwrap();
+        assert_eq!(stm.get_state(), State::Initialized);
+        stm.start().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        // The stream plays on the device.
+        assert_eq!(stm.get_state(), State::Started);
+        assert!(stm.position() > 0);
+        stm.stop().unwrap();
+        assert_eq!(stm.get_state(), State::Stopped);
+    }
+}
+
+#[test]
+fn test_stream_moved_after_creation() {
+    let stm = Stream::<f32>::new(2, 44_100.0, callback_f32).unwrap();
+    // The callback context lives on the heap, so moving the stream into
+    // another container after creation must keep the stream working.
+    let streams = vec![stm];
+    let boxed = Box::new(streams);
+    boxed[0].start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    boxed[0].stop().unwrap();
+}
+
+// Stream::new_interleaved
+// ------------------------------------
+#[test]
+fn test_stream_new_interleaved() {
+    use std::sync::mpsc;
+
+    let (sender, receiver) = mpsc::channel();
+    let stm = Stream::<f32>::new_interleaved(2, 44_100.0, move |mut buffer, _timing| {
+        for frame in buffer.frames_mut() {
+            for data in frame.iter_mut() {
+                *data = 0.0;
+            }
+        }
+        let _ = sender.send((buffer.get_channels(), buffer.get_frames(), buffer.len()));
+        buffer.get_frames()
+    })
+    .unwrap();
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    stm.stop().unwrap();
+    for (channels, frames, len) in receiver.try_iter() {
+        assert_eq!(channels, 2);
+        assert_eq!(frames * channels, len);
+    }
+}
+
+#[test]
+fn test_stream_with_packed_24_bit_samples() {
+    let stm = Stream::<I24>::new(2, 44_100.0, |buffers, _timing| {
+        for buffer in buffers.iter_mut() {
+            for data in buffer.iter_mut() {
+                *data = I24::new(0);
+            }
+        }
+        buffers[0].len()
+    })
+    .unwrap();
+    stm.start().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    stm.stop().unwrap();
+}
+
+// Tests for Private Functions
+// ============================================================================
+
+// Parameters::to_description
+// ------------------------------------
+#[test]
+fn test_to_description_formats() {
+    let formats = [
+        (Format::U8, 1, 8, 0),
+        (Format::S16LE, 2, 16, sys::kAudioFormatFlagIsSignedInteger),
+        (
+            Format::S16BE,
+            2,
+            16,
+            sys::kAudioFormatFlagIsSignedInteger | sys::kAudioFormatFlagIsBigEndian,
+        ),
+        (Format::S24LE, 3, 24, sys::kAudioFormatFlagIsSignedInteger),
+        (
+            Format::S24BE,
+            3,
+            24,
+            sys::kAudioFormatFlagIsSignedInteger | sys::kAudioFormatFlagIsBigEndian,
+        ),
+        (
+            Format::S24_32LE,
+            4,
+            24,
+            sys::kAudioFormatFlagIsSignedInteger,
+        ),
+        (
+            Format::S24_32BE,
+            4,
+            24,
+            sys::kAudioFormatFlagIsSignedInteger | sys::kAudioFormatFlagIsBigEndian,
+        ),
+        (Format::S32LE, 4, 32, sys::kAudioFormatFlagIsSignedInteger),
+        (
+            Format::S32BE,
+            4,
+            32,
+            sys::kAudioFormatFlagIsSignedInteger | sys::kAudioFormatFlagIsBigEndian,
+        ),
+        (Format::F32LE, 4, 32, sys::kAudioFormatFlagIsFloat),
+        (
+            Format::F32BE,
+            4,
+            32,
+            sys::kAudioFormatFlagIsFloat | sys::kAudioFormatFlagIsBigEndian,
+        ),
+        (Format::F64LE, 8, 64, sys::kAudioFormatFlagIsFloat),
+        (
+            Format::F64BE,
+            8,
+            64,
+            sys::kAudioFormatFlagIsFloat | sys::kAudioFormatFlagIsBigEndian,
+        ),
+    ];
+    let type_flags = sys::kAudioFormatFlagIsFloat
+        | sys::kAudioFormatFlagIsSignedInteger
+        | sys::kAudioFormatFlagIsBigEndian;
+    for &(ref format, bytes, bits, flags) in formats.iter() {
+        let description = Parameters::new(1, format.clone(), 48_000.0).to_description();
+        assert_eq!(description.mBytesPerFrame, bytes);
+        assert_eq!(description.mBitsPerChannel, bits);
+        assert_eq!(description.mFormatFlags & type_flags, flags);
+        // Only the 24-bit samples in 32-bit containers are not packed.
+        assert_eq!(
+            description.mFormatFlags & sys::kLinearPCMFormatFlagIsPacked != 0,
+            bits == bytes * 8
+        );
+        assert_eq!(
+            description.mFormatFlags & sys::kLinearPCMFormatFlagIsAlignedHigh,
+            0
+        );
+    }
+}
+
+#[test]
+fn test_to_description_non_interleaved() {
+    let description = Parameters::new(2, Format::S16LE, 44_100.0).to_description();
+    assert_eq!(description.mBytesPerFrame, 2);
+    assert_eq!(description.mBytesPerPacket, 2);
+    assert_eq!(description.mChannelsPerFrame, 2);
+    assert_ne!(
+        description.mFormatFlags & sys::kLinearPCMFormatFlagIsNonInterleaved,
+        0
+    );
+}
+
+#[test]
+fn test_to_description_interleaved() {
+    let description = Parameters::new(2, Format::F32LE, 48_000.0)
+        .with_layout(Layout::Interleaved)
+        .to_description();
+    assert_eq!(description.mBytesPerFrame, 8);
+    assert_eq!(description.mBytesPerPacket, 8);
+    assert_eq!(description.mChannelsPerFrame, 2);
+    assert_eq!(
+        description.mFormatFlags & sys::kLinearPCMFormatFlagIsNonInterleaved,
+        0
+    );
+}
+
+// fill_silence
+// ------------------------------------
+#[test]
+fn test_fill_silence() {
+    let mut left = [1_u8; 4];
+    let mut right = [1_u8; 4];
+    let mut buffers = [
+        sys::AudioBuffer {
+            mNumberChannels: 1,
+            mDataByteSize: 4,
+            mData: left.as_mut_ptr() as *mut c_void,
+        },
+        sys::AudioBuffer {
+            mNumberChannels: 1,
+            mDataByteSize: 4,
+            mData: right.as_mut_ptr() as *mut c_void,
+        },
+    ];
+    fill_silence::<u8>(&mut buffers, 3);
+    assert_eq!(left, [1, 1, 1, 128]);
+    assert_eq!(right, [1, 1, 1, 128]);
+}
+
+#[test]
+fn test_fill_silence_interleaved() {
+    let mut data = [1.0_f32; 6];
+    let mut buffers = [sys::AudioBuffer {
+        mNumberChannels: 2,
+        mDataByteSize: (data.len() * size_of::<f32>()) as u32,
+        mData: data.as_mut_ptr() as *mut c_void,
+    }];
+    fill_silence::<f32>(&mut buffers, 1);
+    assert_eq!(data, [1.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+    // Filling from the end changes nothing.
+    fill_silence::<f32>(&mut buffers, 5);
+    assert_eq!(data, [1.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+}
+
+// check_input_rate
+// ------------------------------------
+#[test]
+fn test_check_input_rate() {
+    if let Ok(device) = utils::get_default_device(&Scope::Input) {
+        let rate = device.get_rate(&Scope::Input).unwrap();
+        assert!(check_input_rate(&device, rate).is_ok());
+        match check_input_rate(&device, rate + 1.0) {
+            Err(Error::UnsupportedRate(r)) => assert_eq!(r, rate),
+            _ => panic!("The rate different from the device rate should fail"),
+        }
+    }
+}
+
+#[test]
+fn test_check_input_rate_with_invalid_device() {
+    let unknown_device = AudioObject::default();
+    match check_input_rate(&unknown_device, 44_100.0) {
+        Err(Error::Utils(_)) => {}
+        _ => panic!("The unknown device should fail"),
+    }
+}
+
+// check_scope
+// ------------------------------------
+#[test]
+fn test_check_scope_with_invalid_device() {
+    let unknown_device = AudioObject::default();
+    match check_scope(&unknown_device, &Scope::Output) {
+        Err(Error::Utils(_)) => {}
+        _ => panic!("The unknown device should fail"),
+    }
+}
@@ -0,0 +1,85 @@
+extern crate coreaudio_sys as sys;
+
+// The timing of the buffers passed to a stream callback, converted from the
+// `AudioTimeStamp` of the render callback. A field is meaningful only if it's
+// marked valid by `flags`, which can be checked by the `has_*` functions.
+//
+// The `sample_time` is the position of the first frame in the buffers, in
+// the sample clock of the device, and the `host_time` is when the first frame
+// is played, or captured for the input data, in nanoseconds of the host
+// clock. The `rate_scalar` is the ratio of the actual rate to the nominal
+// rate of the device.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Timing {
+    pub sample_time: f64,
+    pub host_time: u64,
+    pub rate_scalar: f64,
+    pub flags: sys::AudioTimeStampFlags,
+}
+
+impl Timing {
+    pub fn has_sample_time(&self) -> bool {
+        self.flags & sys::kAudioTimeStampSampleTimeValid != 0
+    }
+
+    pub fn has_host_time(&self) -> bool {
+        self.flags & sys::kAudioTimeStampHostTimeValid != 0
+    }
+
+    pub fn has_rate_scalar(&self) -> bool {
+        self.flags & sys::kAudioTimeStampRateScalarValid != 0
+    }
+
+    // The host time is in the ticks of the host clock, so it's converted to
+    // nanoseconds by `convert_host_time`.
+    fn from_time_stamp<F>(time_stamp: &sys::AudioTimeStamp, convert_host_time: F) -> Self
+    where
+        F: FnOnce(u64) -> u64,
+    {
+        let mut timing = Timing {
+            sample_time: 0.0,
+            host_time: 0,
+            rate_scalar: 0.0,
+            flags: time_stamp.mFlags,
+        };
+        if timing.has_sample_time() {
+            timing.sample_time = time_stamp.mSampleTime;
+        }
+        if timing.has_host_time() {
+            timing.host_time = convert_host_time(time_stamp.mHostTime);
+        }
+        if timing.has_rate_scalar() {
+            timing.rate_scalar = time_stamp.mRateScalar;
+        }
+        timing
+    }
+}
+
+// Return an empty `Timing` if there is no time stamp.
+pub fn get_timing(time_stamp: *const sys::AudioTimeStamp) -> Timing {
+    if time_stamp.is_null() {
+        return Timing::default();
+    }
+    unsafe { Timing::from_time_stamp(&*time_stamp, convert_host_time_to_nanos) }
+}
+
+// Get the current time of the host clock in nanoseconds, which is comparable
+// to `Timing::host_time`.
+pub fn get_current_host_time() -> u64 {
+    convert_host_time_to_nanos(audio_get_current_host_time())
+}
+
+// Private APIs
+// ============================================================================
+fn convert_host_time_to_nanos(host_time: u64) -> u64 {
+    unsafe { sys::AudioConvertHostTimeToNanos(host_time) }
+}
+
+fn audio_get_current_host_time() -> u64 {
+    unsafe { sys::AudioGetCurrentHostTime() }
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
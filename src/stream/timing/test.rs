@@ -0,0 +1,82 @@
+extern crate coreaudio_sys as sys;
+
+use super::*;
+use std::mem;
+use std::ptr;
+
+fn get_fake_time_stamp(flags: sys::AudioTimeStampFlags) -> sys::AudioTimeStamp {
+    let mut time_stamp: sys::AudioTimeStamp = unsafe { mem::zeroed() };
+    time_stamp.mSampleTime = 512.0;
+    time_stamp.mHostTime = 1_000;
+    time_stamp.mRateScalar = 1.001;
+    time_stamp.mFlags = flags;
+    time_stamp
+}
+
+// Tests for Public Functions
+// ============================================================================
+
+// get_timing
+// ------------------------------------
+#[test]
+fn test_get_timing_without_time_stamp() {
+    let timing = get_timing(ptr::null());
+    assert_eq!(timing, Timing::default());
+    assert!(!timing.has_sample_time());
+    assert!(!timing.has_host_time());
+    assert!(!timing.has_rate_scalar());
+}
+
+#[test]
+fn test_get_timing() {
+    let time_stamp = get_fake_time_stamp(
+        sys::kAudioTimeStampSampleTimeValid | sys::kAudioTimeStampHostTimeValid,
+    );
+    let timing = get_timing(&time_stamp);
+    assert!(timing.has_sample_time());
+    assert_eq!(timing.sample_time, 512.0);
+    assert!(timing.has_host_time());
+    assert_eq!(timing.host_time, convert_host_time_to_nanos(1_000));
+    assert!(!timing.has_rate_scalar());
+}
+
+// get_current_host_time
+// ------------------------------------
+#[test]
+fn test_get_current_host_time() {
+    let earlier = get_current_host_time();
+    let later = get_current_host_time();
+    assert!(earlier > 0);
+    assert!(earlier <= later);
+}
+
+// Tests for Private Functions
+// ============================================================================
+
+// Timing::from_time_stamp
+// ------------------------------------
+#[test]
+fn test_from_time_stamp() {
+    let all = sys::kAudioTimeStampSampleTimeValid
+        | sys::kAudioTimeStampHostTimeValid
+        | sys::kAudioTimeStampRateScalarValid;
+    let timing = Timing::from_time_stamp(&get_fake_time_stamp(all), |time| time * 2);
+    assert_eq!(
+        timing,
+        Timing {
+            sample_time: 512.0,
+            host_time: 2_000,
+            rate_scalar: 1.001,
+            flags: all,
+        }
+    );
+}
+
+#[test]
+fn test_from_time_stamp_ignores_invalid_fields() {
+    let time_stamp = get_fake_time_stamp(sys::kAudioTimeStampRateScalarValid);
+    let timing = Timing::from_time_stamp(&time_stamp, |_| panic!("Host time is invalid"));
+    assert_eq!(timing.sample_time, 0.0);
+    assert_eq!(timing.host_time, 0);
+    assert_eq!(timing.rate_scalar, 1.001);
+}
@@ -0,0 +1,115 @@
+extern crate coreaudio_sys as sys;
+
+use std::mem::size_of;
+use std::slice;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use super::Sample;
+
+// The time to move from one gain to another, so changing the volume doesn't
+// click.
+const RAMP_SECONDS: f64 = 0.01;
+
+// The volume of the stream. It's applied by the unit when the unit supports
+// it. Otherwise it's applied to the rendered samples as a gain by the
+// `Gain` of the audio thread, which moves linearly to the new volume in
+// `RAMP_SECONDS` after every change.
+pub struct Volume {
+    // The bits of the `f32`s shared with the audio thread.
+    volume: AtomicU32,
+    target: AtomicU32, // The gain to apply to the samples.
+}
+
+impl Volume {
+    pub fn new() -> Self {
+        Volume {
+            volume: AtomicU32::new(1.0_f32.to_bits()),
+            target: AtomicU32::new(1.0_f32.to_bits()),
+        }
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.volume.load(Ordering::SeqCst))
+    }
+
+    // The samples are left untouched if `by_unit` is `true`.
+    pub fn set(&self, volume: f32, by_unit: bool) {
+        let target = if by_unit { 1.0 } else { volume };
+        self.volume.store(volume.to_bits(), Ordering::SeqCst);
+        self.target.store(target.to_bits(), Ordering::SeqCst);
+    }
+
+    fn get_target(&self) -> f32 {
+        f32::from_bits(self.target.load(Ordering::SeqCst))
+    }
+}
+
+// The gain applied to the samples. It's only touched on the audio thread.
+pub struct Gain {
+    gain: f32,
+    end: f32,
+    step: f32,
+    remaining: usize,
+    ramp_frames: usize,
+}
+
+impl Gain {
+    pub fn new(rate: f64) -> Self {
+        Gain {
+            gain: 1.0,
+            end: 1.0,
+            step: 0.0,
+            remaining: 0,
+            ramp_frames: ((rate * RAMP_SECONDS).ceil() as usize).max(1),
+        }
+    }
+
+    // Apply the gain of the volume to the first `frames` frames in the
+    // buffers. Called on the audio thread.
+    pub fn apply<T: Sample>(
+        &mut self,
+        volume: &Volume,
+        buffers: &mut [sys::AudioBuffer],
+        frames: usize,
+    ) {
+        let target = volume.get_target();
+        if target != self.end {
+            self.end = target;
+            self.step = (target - self.gain) / self.ramp_frames as f32;
+            self.remaining = self.ramp_frames;
+        }
+        if self.remaining == 0 && self.gain == 1.0 {
+            return;
+        }
+        let gains = (0..frames).map(|_| self.next_gain());
+        for (frame, gain) in gains.enumerate() {
+            for buffer in buffers.iter_mut() {
+                let channels = buffer.mNumberChannels as usize;
+                let len = buffer.mDataByteSize as usize / size_of::<T>();
+                let samples = unsafe { slice::from_raw_parts_mut(buffer.mData as *mut T, len) };
+                let from = (frame * channels).min(len);
+                let to = (from + channels).min(len);
+                for sample in samples[from..to].iter_mut() {
+                    *sample = T::from_f64(sample.to_f64() * f64::from(gain));
+                }
+            }
+        }
+    }
+
+    fn next_gain(&mut self) -> f32 {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.gain = if self.remaining == 0 {
+                self.end
+            } else {
+                self.gain + self.step
+            };
+        }
+        self.gain
+    }
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
@@ -0,0 +1,144 @@
+extern crate coreaudio_sys as sys;
+
+use super::*;
+use std::mem::size_of_val;
+use std::os::raw::c_void;
+
+fn get_buffer(data: &mut [f32], channels: u32) -> sys::AudioBuffer {
+    sys::AudioBuffer {
+        mNumberChannels: channels,
+        mDataByteSize: size_of_val(data) as u32,
+        mData: data.as_mut_ptr() as *mut c_void,
+    }
+}
+
+// Tests for Public Functions
+// ============================================================================
+
+// Volume::new
+// ------------------------------------
+#[test]
+fn test_new() {
+    let volume = Volume::new();
+    assert_eq!(volume.get(), 1.0);
+    // The samples are untouched at the full volume.
+    let mut gain = Gain::new(1000.0);
+    let mut data = [0.5_f32; 4];
+    gain.apply::<f32>(&volume, &mut [get_buffer(&mut data, 1)], 4);
+    assert_eq!(data, [0.5; 4]);
+}
+
+// Volume::set
+// Volume::get
+// ------------------------------------
+#[test]
+fn test_set() {
+    let volume = Volume::new();
+    volume.set(0.5, false);
+    assert_eq!(volume.get(), 0.5);
+    volume.set(0.25, true);
+    assert_eq!(volume.get(), 0.25);
+}
+
+// Gain::apply
+// ------------------------------------
+#[test]
+fn test_apply_ramp() {
+    let volume = Volume::new();
+    // Ramp in 10 frames.
+    let mut gain = Gain::new(1000.0);
+    volume.set(0.0, false);
+    let mut data = [1.0_f32; 16];
+    gain.apply::<f32>(&volume, &mut [get_buffer(&mut data, 1)], 16);
+    assert!(data
+        .windows(2)
+        .all(|pair| pair[0] > pair[1] || pair[1] == 0.0));
+    assert!(data[0] > 0.8);
+    assert_eq!(data[9..], [0.0; 7]);
+}
+
+#[test]
+fn test_apply_ramp_across_buffers() {
+    let volume = Volume::new();
+    let mut gain = Gain::new(1000.0);
+    volume.set(0.5, false);
+    let mut first = [1.0_f32; 5];
+    gain.apply::<f32>(&volume, &mut [get_buffer(&mut first, 1)], 5);
+    let mut second = [1.0_f32; 8];
+    gain.apply::<f32>(&volume, &mut [get_buffer(&mut second, 1)], 8);
+    assert!(first[4] > second[0]);
+    assert!(second[0] > 0.5);
+    assert_eq!(second[4..], [0.5; 4]);
+}
+
+#[test]
+fn test_apply_interleaved() {
+    let volume = Volume::new();
+    let mut gain = Gain::new(1000.0);
+    volume.set(0.0, false);
+    let mut data = [1.0_f32; 24];
+    gain.apply::<f32>(&volume, &mut [get_buffer(&mut data, 2)], 12);
+    // Both channels of a frame get the same gain.
+    for frame in data.chunks(2) {
+        assert_eq!(frame[0], frame[1]);
+    }
+    assert_eq!(data[18..], [0.0; 6]);
+}
+
+#[test]
+fn test_apply_non_interleaved() {
+    let volume = Volume::new();
+    let mut gain = Gain::new(1000.0);
+    volume.set(0.0, false);
+    let mut left = [1.0_f32; 12];
+    let mut right = [1.0_f32; 12];
+    gain.apply::<f32>(
+        &volume,
+        &mut [get_buffer(&mut left, 1), get_buffer(&mut right, 1)],
+        12,
+    );
+    assert_eq!(left, right);
+    assert_eq!(left[9..], [0.0; 3]);
+}
+
+#[test]
+fn test_apply_produced_frames_only() {
+    let volume = Volume::new();
+    let mut gain = Gain::new(1000.0);
+    volume.set(0.0, false);
+    let mut data = [1.0_f32; 16];
+    gain.apply::<f32>(&volume, &mut [get_buffer(&mut data, 1)], 12);
+    assert_eq!(data[12..], [1.0; 4]);
+}
+
+#[test]
+fn test_apply_by_unit() {
+    let volume = Volume::new();
+    let mut gain = Gain::new(1000.0);
+    volume.set(0.5, false);
+    let mut data = [1.0_f32; 16];
+    gain.apply::<f32>(&volume, &mut [get_buffer(&mut data, 1)], 16);
+    assert_eq!(data[15], 0.5);
+    // The gain goes back to 1 when the unit applies the volume.
+    volume.set(0.5, true);
+    let mut data = [1.0_f32; 16];
+    gain.apply::<f32>(&volume, &mut [get_buffer(&mut data, 1)], 16);
+    assert!(data[0] < 1.0);
+    assert_eq!(data[10..], [1.0; 6]);
+}
+
+#[test]
+fn test_apply_u8() {
+    let volume = Volume::new();
+    let mut gain = Gain::new(1000.0);
+    volume.set(0.0, false);
+    let mut data = [255_u8; 12];
+    let mut buffers = [sys::AudioBuffer {
+        mNumberChannels: 1,
+        mDataByteSize: 12,
+        mData: data.as_mut_ptr() as *mut c_void,
+    }];
+    gain.apply::<u8>(&volume, &mut buffers, 12);
+    // The silence of u8 is 128.
+    assert_eq!(data[9..], [128; 3]);
+}
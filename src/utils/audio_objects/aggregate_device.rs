@@ -0,0 +1,229 @@
+extern crate coreaudio_sys;
+
+use self::coreaudio_sys::{kAudioSubDeviceClassID, AudioClassID};
+use super::audio_object_utils::{create_aggregate_device, destroy_aggregate_device};
+use super::collection_wrapper::{ArrayRef, DictionaryRef, Value};
+use super::property_address::{get_global_property_address, Property};
+use super::string_wrapper::StringRef;
+use super::{AudioObject, Error, GetObjectId, GetPropertyArray, GetPropertyData, SetPropertyData};
+
+use std::ops::Deref;
+
+// The keys used in the description dictionary. See the
+// `kAudioAggregateDevice*Key` and `kAudioSubDevice*Key` in AudioHardware.h.
+const AGGREGATE_DEVICE_UID_KEY: &str = "uid";
+const AGGREGATE_DEVICE_NAME_KEY: &str = "name";
+const AGGREGATE_DEVICE_SUB_DEVICE_LIST_KEY: &str = "subdevices";
+const AGGREGATE_DEVICE_MASTER_SUB_DEVICE_KEY: &str = "master";
+const AGGREGATE_DEVICE_IS_PRIVATE_KEY: &str = "private";
+const SUB_DEVICE_UID_KEY: &str = "uid";
+const SUB_DEVICE_DRIFT_COMPENSATION_KEY: &str = "drift";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubDevice {
+    pub uid: String,
+    pub drift_compensation: bool,
+}
+
+impl SubDevice {
+    pub fn new(uid: &str, drift_compensation: bool) -> Self {
+        SubDevice {
+            uid: uid.to_string(),
+            drift_compensation,
+        }
+    }
+
+    fn to_dictionary(&self) -> Result<DictionaryRef, Error> {
+        DictionaryRef::from_pairs(vec![
+            (SUB_DEVICE_UID_KEY, Value::from(self.uid.as_str())),
+            (
+                SUB_DEVICE_DRIFT_COMPENSATION_KEY,
+                Value::from(self.drift_compensation),
+            ),
+        ])
+        .map_err(Error::CollectionFailed)
+    }
+}
+
+// The `clock_master` is the UID of the sub-device providing the clock for the
+// aggregate device. A private aggregate device is only visible to the process
+// creating it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateDeviceDescription {
+    pub name: String,
+    pub uid: String,
+    pub sub_devices: Vec<SubDevice>,
+    pub clock_master: String,
+    pub private: bool,
+}
+
+impl AggregateDeviceDescription {
+    pub fn new(
+        name: &str,
+        uid: &str,
+        sub_devices: Vec<SubDevice>,
+        clock_master: &str,
+        private: bool,
+    ) -> Self {
+        AggregateDeviceDescription {
+            name: name.to_string(),
+            uid: uid.to_string(),
+            sub_devices,
+            clock_master: clock_master.to_string(),
+            private,
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let has_clock_master = self
+            .sub_devices
+            .iter()
+            .any(|sub_device| sub_device.uid == self.clock_master);
+        if self.uid.is_empty() || !has_clock_master {
+            Err(Error::InvalidDescription)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn to_dictionary(&self) -> Result<DictionaryRef, Error> {
+        let mut sub_devices = Vec::new();
+        for sub_device in &self.sub_devices {
+            sub_devices.push(Value::Dictionary(sub_device.to_dictionary()?));
+        }
+        let sub_devices = ArrayRef::from_values(sub_devices).map_err(Error::CollectionFailed)?;
+        DictionaryRef::from_pairs(vec![
+            (AGGREGATE_DEVICE_UID_KEY, Value::from(self.uid.as_str())),
+            (AGGREGATE_DEVICE_NAME_KEY, Value::from(self.name.as_str())),
+            (
+                AGGREGATE_DEVICE_SUB_DEVICE_LIST_KEY,
+                Value::Array(sub_devices),
+            ),
+            (
+                AGGREGATE_DEVICE_MASTER_SUB_DEVICE_KEY,
+                Value::from(self.clock_master.as_str()),
+            ),
+            (AGGREGATE_DEVICE_IS_PRIVATE_KEY, Value::from(self.private)),
+        ])
+        .map_err(Error::CollectionFailed)
+    }
+}
+
+// AggregateDevice
+// ============================================================================
+// The aggregate device will be destroyed when its handle is dropped.
+pub struct AggregateDevice(AudioObject);
+
+impl AggregateDevice {
+    pub fn new(description: &AggregateDeviceDescription) -> Result<Self, Error> {
+        description.validate()?;
+        let dictionary = description.to_dictionary()?;
+        let id = create_aggregate_device(dictionary.get_raw())?;
+        Ok(AggregateDevice(AudioObject::new(id)))
+    }
+
+    // Return the sub-devices in the order of the full sub-device list. The
+    // drift compensation is reported as `false` for those sub-devices that
+    // are not active now (e.g., unplugged).
+    pub fn get_sub_devices(&self) -> Result<Vec<SubDevice>, Error> {
+        let uids = self.get_sub_device_uids()?;
+        let active_sub_devices = self.get_active_sub_devices()?;
+        let mut sub_devices = Vec::new();
+        for uid in uids {
+            let mut drift_compensation = false;
+            for sub_device in &active_sub_devices {
+                if sub_device.get_uid()? == uid {
+                    drift_compensation = get_drift_compensation(sub_device)?;
+                    break;
+                }
+            }
+            sub_devices.push(SubDevice::new(&uid, drift_compensation));
+        }
+        Ok(sub_devices)
+    }
+
+    pub fn set_sub_devices(&self, sub_devices: &[SubDevice]) -> Result<(), Error> {
+        let uids: Vec<&str> = sub_devices
+            .iter()
+            .map(|sub_device| sub_device.uid.as_str())
+            .collect();
+        let list = ArrayRef::from_strings(&uids).map_err(Error::CollectionFailed)?;
+        let address = get_global_property_address(Property::AggregateDeviceSubDeviceList);
+        self.0.set_property_data(&address, &list)?;
+
+        for sub_device in self.get_active_sub_devices()? {
+            let uid = sub_device.get_uid()?;
+            if let Some(settings) = sub_devices.iter().find(|s| s.uid == uid) {
+                set_drift_compensation(&sub_device, settings.drift_compensation)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_clock_master(&self) -> Result<String, Error> {
+        let address = get_global_property_address(Property::AggregateDeviceMasterSubDevice);
+        let uid: StringRef = self.0.get_property_data(&address)?;
+        uid.into_string().map_err(Error::ConversionFailed)
+    }
+
+    pub fn set_clock_master(&self, uid: &str) -> Result<(), Error> {
+        if !self.get_sub_device_uids()?.iter().any(|u| u == uid) {
+            return Err(Error::InvalidDescription);
+        }
+        let address = get_global_property_address(Property::AggregateDeviceMasterSubDevice);
+        let uid = StringRef::from(uid);
+        self.0.set_property_data(&address, &uid)
+    }
+
+    fn get_sub_device_uids(&self) -> Result<Vec<String>, Error> {
+        let address = get_global_property_address(Property::AggregateDeviceSubDeviceList);
+        let list: ArrayRef = self.0.get_property_data(&address)?;
+        list.to_strings().map_err(Error::CollectionFailed)
+    }
+
+    // The sub-devices owned by the aggregate device are different objects
+    // from the devices they represent, but they share the same UIDs.
+    fn get_active_sub_devices(&self) -> Result<Vec<AudioObject>, Error> {
+        let address = get_global_property_address(Property::OwnedObjects);
+        let objects: Vec<AudioObject> = self.0.get_property_array(&address)?;
+        let mut sub_devices = Vec::new();
+        for object in objects {
+            let address = get_global_property_address(Property::ObjectClass);
+            let class: AudioClassID = object.get_property_data(&address)?;
+            if class == kAudioSubDeviceClassID {
+                sub_devices.push(object);
+            }
+        }
+        Ok(sub_devices)
+    }
+}
+
+impl Deref for AggregateDevice {
+    type Target = AudioObject;
+
+    fn deref(&self) -> &AudioObject {
+        &self.0
+    }
+}
+
+impl Drop for AggregateDevice {
+    // Don't panic here since the device may already be removed by the system,
+    // or the device may be dropped while unwinding.
+    fn drop(&mut self) {
+        let _ = destroy_aggregate_device(self.0.get_id());
+    }
+}
+
+// Utils
+// ============================================================================
+fn get_drift_compensation(sub_device: &AudioObject) -> Result<bool, Error> {
+    let address = get_global_property_address(Property::SubDeviceDriftCompensation);
+    let drift_compensation: u32 = sub_device.get_property_data(&address)?;
+    Ok(drift_compensation != 0)
+}
+
+fn set_drift_compensation(sub_device: &AudioObject, enabled: bool) -> Result<(), Error> {
+    let address = get_global_property_address(Property::SubDeviceDriftCompensation);
+    let drift_compensation = enabled as u32;
+    sub_device.set_property_data(&address, &drift_compensation)
+}
@@ -0,0 +1,59 @@
+use super::property_address::{get_global_property_address, Property};
+use super::string_wrapper::StringRef;
+use super::{get_object_list, AudioObject, Error, GetPropertyData, SetPropertyData, TransportType};
+
+use std::ops::Deref;
+
+// AudioBox
+// ============================================================================
+// A piece of hardware that may contain several devices. The devices in a box
+// are only available to the system when the box is acquired.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioBox(AudioObject);
+
+impl AudioBox {
+    pub fn new(object: AudioObject) -> Self {
+        AudioBox(object)
+    }
+
+    pub fn get_uid(&self) -> Result<String, Error> {
+        let address = get_global_property_address(Property::BoxUID);
+        let uid: StringRef = self.0.get_property_data(&address)?;
+        uid.into_string().map_err(Error::ConversionFailed)
+    }
+
+    pub fn get_transport_type(&self) -> Result<TransportType, Error> {
+        let address = get_global_property_address(Property::BoxTransportType);
+        let transport_type: u32 = self.0.get_property_data(&address)?;
+        Ok(TransportType::from(transport_type))
+    }
+
+    pub fn has_audio(&self) -> Result<bool, Error> {
+        let address = get_global_property_address(Property::BoxHasAudio);
+        let has_audio: u32 = self.0.get_property_data(&address)?;
+        Ok(has_audio != 0)
+    }
+
+    pub fn is_acquired(&self) -> Result<bool, Error> {
+        let address = get_global_property_address(Property::BoxAcquired);
+        let acquired: u32 = self.0.get_property_data(&address)?;
+        Ok(acquired != 0)
+    }
+
+    pub fn set_acquired(&self, acquired: bool) -> Result<(), Error> {
+        let address = get_global_property_address(Property::BoxAcquired);
+        self.0.set_property_data(&address, &(acquired as u32))
+    }
+
+    pub fn get_devices(&self) -> Result<Vec<AudioObject>, Error> {
+        get_object_list(&self.0, Property::BoxDevices)
+    }
+}
+
+impl Deref for AudioBox {
+    type Target = AudioObject;
+
+    fn deref(&self) -> &AudioObject {
+        &self.0
+    }
+}
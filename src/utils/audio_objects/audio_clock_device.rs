@@ -0,0 +1,59 @@
+use super::property_address::{get_global_property_address, Property};
+use super::string_wrapper::StringRef;
+use super::{AudioObject, Error, GetPropertyData, TransportType};
+
+use std::ops::Deref;
+
+// AudioClockDevice
+// ============================================================================
+// A device providing only a clock, e.g., a word clock generator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioClockDevice(AudioObject);
+
+impl AudioClockDevice {
+    pub fn new(object: AudioObject) -> Self {
+        AudioClockDevice(object)
+    }
+
+    pub fn get_uid(&self) -> Result<String, Error> {
+        let address = get_global_property_address(Property::ClockDeviceUID);
+        let uid: StringRef = self.0.get_property_data(&address)?;
+        uid.into_string().map_err(Error::ConversionFailed)
+    }
+
+    pub fn get_transport_type(&self) -> Result<TransportType, Error> {
+        let address = get_global_property_address(Property::ClockDeviceTransportType);
+        let transport_type: u32 = self.0.get_property_data(&address)?;
+        Ok(TransportType::from(transport_type))
+    }
+
+    // See `AudioObject::get_clock_domain`.
+    pub fn get_clock_domain(&self) -> Result<u32, Error> {
+        let address = get_global_property_address(Property::ClockDeviceDomain);
+        self.0.get_property_data(&address)
+    }
+
+    pub fn is_alive(&self) -> Result<bool, Error> {
+        let address = get_global_property_address(Property::ClockDeviceIsAlive);
+        let alive: u32 = self.0.get_property_data(&address)?;
+        Ok(alive != 0)
+    }
+
+    pub fn get_rate(&self) -> Result<f64, Error> {
+        let address = get_global_property_address(Property::ClockDeviceRate);
+        self.0.get_property_data(&address)
+    }
+
+    pub fn get_latency(&self) -> Result<u32, Error> {
+        let address = get_global_property_address(Property::ClockDeviceLatency);
+        self.0.get_property_data(&address)
+    }
+}
+
+impl Deref for AudioClockDevice {
+    type Target = AudioObject;
+
+    fn deref(&self) -> &AudioObject {
+        &self.0
+    }
+}
@@ -16,8 +16,11 @@
 // block and it will match to all given `status`. It's not the
 // `kAudioHardwareBadObjectError` defined in a `OSStatus` enum in CoreAudio
 // as we expected.
+extern crate core_foundation_sys;
 extern crate coreaudio_sys as sys;
 
+use self::core_foundation_sys::dictionary::CFDictionaryRef;
+
 use std::fmt; // For fmt::{Debug, Formatter, Result}
 use std::mem; // For mem::{size_of_val, size_of}
 use std::os::raw::c_void;
@@ -26,10 +29,18 @@ use std::ptr; // For ptr::null()
 // Using PartialEq for comparison.
 #[derive(PartialEq)]
 pub enum Error {
+    BadDevice,
     BadObject,
     BadPropertySize,
+    IllegalOperation,
     UnknownProperty,
+    UnsupportedFormat,
+    UnsupportedOperation,
+    Unspecified,
     SizeIsZero,
+    // The status without a specific variant, e.g., the permission error when
+    // another process hogs the device.
+    Other(sys::OSStatus),
 }
 
 impl From<sys::OSStatus> for Error {
@@ -40,10 +51,15 @@ impl From<sys::OSStatus> for Error {
         }
 
         match to_bindgen_type(status) {
+            sys::kAudioHardwareBadDeviceError => Error::BadDevice,
             sys::kAudioHardwareBadObjectError => Error::BadObject,
             sys::kAudioHardwareBadPropertySizeError => Error::BadPropertySize,
+            sys::kAudioHardwareIllegalOperationError => Error::IllegalOperation,
             sys::kAudioHardwareUnknownPropertyError => Error::UnknownProperty,
-            s => panic!("Unknown status: {}", s),
+            sys::kAudioDeviceUnsupportedFormatError => Error::UnsupportedFormat,
+            sys::kAudioHardwareUnsupportedOperationError => Error::UnsupportedOperation,
+            sys::kAudioHardwareUnspecifiedError => Error::Unspecified,
+            _ => Error::Other(status),
         }
     }
 }
@@ -51,10 +67,16 @@ impl From<sys::OSStatus> for Error {
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let printable = match self {
+            Error::BadDevice => "BadDevice: The AudioObjectID passed to the function doesn't map to a valid AudioDevice.",
             Error::BadObject => "BadObject: The AudioObjectID passed to the function doesn't map to a valid AudioObject.",
             Error::BadPropertySize => "BadPropertySize: An improperly sized buffer was provided when accessing the data of a property.",
+            Error::IllegalOperation => "IllegalOperation: The requested operation couldn't be completed.",
             Error::UnknownProperty => "UnknownProperty: The AudioObject doesn't know about the property at the given address.",
+            Error::UnsupportedFormat => "UnsupportedFormat: The AudioStream doesn't support the requested format.",
+            Error::UnsupportedOperation => "UnsupportedOperation: The AudioObject doesn't support the requested operation.",
+            Error::Unspecified => "Unspecified: The function call failed for some reason that is not specified.",
             Error::SizeIsZero => "SizeIsZero: The size of data mapping to the given id and address is zero.",
+            Error::Other(status) => return write!(f, "Other: The function call failed with status {}.", status),
         };
         write!(f, "{}", printable)
     }
@@ -123,6 +145,21 @@ pub fn get_property_variable_sized_data<'a, T>(
     Ok(data)
 }
 
+// Get the data whose size is variable as a byte buffer, so the buffer lives as
+// long as the caller needs.
+pub fn get_property_raw_data(
+    id: sys::AudioObjectID,
+    address: &sys::AudioObjectPropertyAddress,
+) -> Result<Vec<u8>, Error> {
+    // assert!(id != sys::kAudioObjectUnknown, "Bad AudioObjectID!");
+    let mut size = non_empty_size(get_property_data_size(id, address))?;
+    let mut buffer = allocate_array::<u8>(size);
+    let status = audio_object_get_property_data::<u8>(id, address, &mut size, buffer.as_mut_ptr());
+    convert_to_result(status)?;
+    buffer.truncate(size);
+    Ok(buffer)
+}
+
 pub fn set_property_data<T>(
     id: sys::AudioObjectID,
     address: &sys::AudioObjectPropertyAddress,
@@ -134,6 +171,40 @@ pub fn set_property_data<T>(
     convert_to_result(status)
 }
 
+pub fn create_aggregate_device(description: CFDictionaryRef) -> Result<sys::AudioObjectID, Error> {
+    let mut id = sys::kAudioObjectUnknown;
+    let status = audio_hardware_create_aggregate_device(description, &mut id);
+    convert_to_result(status)?;
+    Ok(id)
+}
+
+pub fn destroy_aggregate_device(id: sys::AudioObjectID) -> Result<(), Error> {
+    let status = audio_hardware_destroy_aggregate_device(id);
+    convert_to_result(status)
+}
+
+// The listener is called with the given data on the HAL notification thread
+// until it's removed with the same address, listener and data.
+pub fn add_property_listener(
+    id: sys::AudioObjectID,
+    address: &sys::AudioObjectPropertyAddress,
+    listener: sys::AudioObjectPropertyListenerProc,
+    data: *mut c_void,
+) -> Result<(), Error> {
+    let status = audio_object_add_property_listener(id, address, listener, data);
+    convert_to_result(status)
+}
+
+pub fn remove_property_listener(
+    id: sys::AudioObjectID,
+    address: &sys::AudioObjectPropertyAddress,
+    listener: sys::AudioObjectPropertyListenerProc,
+    data: *mut c_void,
+) -> Result<(), Error> {
+    let status = audio_object_remove_property_listener(id, address, listener, data);
+    convert_to_result(status)
+}
+
 // Private APIs
 // ============================================================================
 fn allocate_array<T>(size: usize) -> Vec<T> {
@@ -215,6 +286,40 @@ fn audio_object_set_property_data<T>(
     }
 }
 
+fn audio_object_add_property_listener(
+    id: sys::AudioObjectID,
+    address: &sys::AudioObjectPropertyAddress,
+    listener: sys::AudioObjectPropertyListenerProc,
+    data: *mut c_void,
+) -> sys::OSStatus {
+    unsafe { sys::AudioObjectAddPropertyListener(id, address, listener, data) }
+}
+
+fn audio_object_remove_property_listener(
+    id: sys::AudioObjectID,
+    address: &sys::AudioObjectPropertyAddress,
+    listener: sys::AudioObjectPropertyListenerProc,
+    data: *mut c_void,
+) -> sys::OSStatus {
+    unsafe { sys::AudioObjectRemovePropertyListener(id, address, listener, data) }
+}
+
+fn audio_hardware_create_aggregate_device(
+    description: CFDictionaryRef,
+    id: *mut sys::AudioObjectID,
+) -> sys::OSStatus {
+    unsafe {
+        sys::AudioHardwareCreateAggregateDevice(
+            description as *const c_void as _, // Cast CFDictionaryRef to sys::CFDictionaryRef.
+            id,
+        )
+    }
+}
+
+fn audio_hardware_destroy_aggregate_device(id: sys::AudioObjectID) -> sys::OSStatus {
+    unsafe { sys::AudioHardwareDestroyAggregateDevice(id) }
+}
+
 // Tests
 // ============================================================================
 #[cfg(test)]
@@ -167,6 +167,62 @@ fn test_get_property_array() {
     }
 }
 
+// add_property_listener
+// remove_property_listener
+// ------------------------------------
+extern "C" fn listener(
+    _id: sys::AudioObjectID,
+    _addresses_count: sys::UInt32,
+    _addresses: *const sys::AudioObjectPropertyAddress,
+    _data: *mut c_void,
+) -> sys::OSStatus {
+    0 // sys::kAudioHardwareNoError.
+}
+
+#[test]
+fn test_add_property_listener_with_invalid_id() {
+    assert_eq!(
+        add_property_listener(
+            sys::kAudioObjectUnknown,
+            &DEVICE_PROPERTY_ADDRESS,
+            Some(listener),
+            ptr::null_mut(),
+        )
+        .unwrap_err(),
+        Error::BadObject
+    );
+}
+
+#[test]
+fn test_add_and_remove_property_listener() {
+    let add = add_property_listener(
+        sys::kAudioObjectSystemObject,
+        &DEFAULT_OUTPUT_DEVICE_PROPERTY_ADDRESS,
+        Some(listener),
+        ptr::null_mut(),
+    );
+    assert!(add.is_ok());
+    let remove = remove_property_listener(
+        sys::kAudioObjectSystemObject,
+        &DEFAULT_OUTPUT_DEVICE_PROPERTY_ADDRESS,
+        Some(listener),
+        ptr::null_mut(),
+    );
+    assert!(remove.is_ok());
+}
+
+// Error::from
+// ------------------------------------
+#[test]
+fn test_error_from_unknown_status() {
+    let status = sys::kAudioDevicePermissionsError as sys::OSStatus;
+    assert_eq!(Error::from(status), Error::Other(status));
+    assert_eq!(
+        Error::from(sys::kAudioHardwareBadObjectError as sys::OSStatus),
+        Error::BadObject
+    );
+}
+
 // Tests for Private Functions
 // ============================================================================
 
@@ -189,3 +245,11 @@ fn test_get_property_array() {
 // audio_object_set_property_data
 // ------------------------------------
 // Skip now ...
+
+// audio_object_add_property_listener
+// ------------------------------------
+// Skip now ...
+
+// audio_object_remove_property_listener
+// ------------------------------------
+// Skip now ...
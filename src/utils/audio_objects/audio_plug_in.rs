@@ -0,0 +1,45 @@
+use super::property_address::{get_global_property_address, Property};
+use super::string_wrapper::StringRef;
+use super::{get_object_list, AudioBox, AudioClockDevice, AudioObject, Error, GetPropertyData};
+
+use std::ops::Deref;
+
+// AudioPlugIn
+// ============================================================================
+// A HAL plug-in providing devices, boxes and clock devices to the system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioPlugIn(AudioObject);
+
+impl AudioPlugIn {
+    pub fn new(object: AudioObject) -> Self {
+        AudioPlugIn(object)
+    }
+
+    pub fn get_bundle_id(&self) -> Result<String, Error> {
+        let address = get_global_property_address(Property::PlugInBundleID);
+        let bundle_id: StringRef = self.0.get_property_data(&address)?;
+        bundle_id.into_string().map_err(Error::ConversionFailed)
+    }
+
+    pub fn get_devices(&self) -> Result<Vec<AudioObject>, Error> {
+        get_object_list(&self.0, Property::PlugInDevices)
+    }
+
+    pub fn get_boxes(&self) -> Result<Vec<AudioBox>, Error> {
+        let objects = get_object_list(&self.0, Property::PlugInBoxes)?;
+        Ok(objects.into_iter().map(AudioBox::new).collect())
+    }
+
+    pub fn get_clock_devices(&self) -> Result<Vec<AudioClockDevice>, Error> {
+        let objects = get_object_list(&self.0, Property::PlugInClockDevices)?;
+        Ok(objects.into_iter().map(AudioClockDevice::new).collect())
+    }
+}
+
+impl Deref for AudioPlugIn {
+    type Target = AudioObject;
+
+    fn deref(&self) -> &AudioObject {
+        &self.0
+    }
+}
@@ -0,0 +1,260 @@
+extern crate coreaudio_sys;
+
+use self::coreaudio_sys::{
+    kAudioFormatFlagIsBigEndian, kAudioFormatFlagIsFloat, kAudioFormatFlagIsNonInterleaved,
+    kAudioFormatFlagIsPacked, kAudioFormatFlagIsSignedInteger, kAudioFormatLinearPCM,
+    AudioFormatFlags, AudioFormatID, AudioStreamBasicDescription, AudioStreamID,
+    AudioStreamRangedDescription,
+};
+use super::property_address::{get_global_property_address, get_scope_property_address, Property};
+use super::{
+    AudioObjectID, Error, GetObjectId, GetPropertyArray, GetPropertyData, Scope, SetPropertyData,
+};
+
+// TerminalType
+// ============================================================================
+// The general kind of the functionality attached to the stream.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TerminalType {
+    DigitalAudioInterface,
+    DisplayPort,
+    HDMI,
+    Headphones,
+    HeadsetMicrophone,
+    LFESpeaker,
+    Line,
+    Microphone,
+    ReceiverMicrophone,
+    ReceiverSpeaker,
+    Speaker,
+    TTY,
+    // The unknown terminal types or the USB terminal types defined in the USB
+    // Audio Class spec.
+    Other(u32),
+}
+
+// Using the `coreaudio_sys` prefix to match the constants. Otherwise they will
+// be new variables introduced in the `match` block.
+impl From<u32> for TerminalType {
+    fn from(terminal_type: u32) -> Self {
+        match terminal_type {
+            coreaudio_sys::kAudioStreamTerminalTypeDigitalAudioInterface => {
+                TerminalType::DigitalAudioInterface
+            }
+            coreaudio_sys::kAudioStreamTerminalTypeDisplayPort => TerminalType::DisplayPort,
+            coreaudio_sys::kAudioStreamTerminalTypeHDMI => TerminalType::HDMI,
+            coreaudio_sys::kAudioStreamTerminalTypeHeadphones => TerminalType::Headphones,
+            coreaudio_sys::kAudioStreamTerminalTypeHeadsetMicrophone => {
+                TerminalType::HeadsetMicrophone
+            }
+            coreaudio_sys::kAudioStreamTerminalTypeLFESpeaker => TerminalType::LFESpeaker,
+            coreaudio_sys::kAudioStreamTerminalTypeLine => TerminalType::Line,
+            coreaudio_sys::kAudioStreamTerminalTypeMicrophone => TerminalType::Microphone,
+            coreaudio_sys::kAudioStreamTerminalTypeReceiverMicrophone => {
+                TerminalType::ReceiverMicrophone
+            }
+            coreaudio_sys::kAudioStreamTerminalTypeReceiverSpeaker => TerminalType::ReceiverSpeaker,
+            coreaudio_sys::kAudioStreamTerminalTypeSpeaker => TerminalType::Speaker,
+            coreaudio_sys::kAudioStreamTerminalTypeTTY => TerminalType::TTY,
+            t => TerminalType::Other(t),
+        }
+    }
+}
+
+// StreamFormat
+// ============================================================================
+// A parsed `AudioStreamBasicDescription`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamFormat {
+    pub rate: f64,
+    pub format_id: AudioFormatID,
+    pub flags: AudioFormatFlags,
+    pub bytes_per_packet: u32,
+    pub frames_per_packet: u32,
+    pub bytes_per_frame: u32,
+    pub channels: u32,
+    pub bits_per_channel: u32,
+}
+
+impl StreamFormat {
+    pub fn is_linear_pcm(&self) -> bool {
+        self.format_id == kAudioFormatLinearPCM
+    }
+
+    pub fn is_float(&self) -> bool {
+        self.flags & kAudioFormatFlagIsFloat != 0
+    }
+
+    pub fn is_signed_integer(&self) -> bool {
+        self.flags & kAudioFormatFlagIsSignedInteger != 0
+    }
+
+    pub fn is_big_endian(&self) -> bool {
+        self.flags & kAudioFormatFlagIsBigEndian != 0
+    }
+
+    pub fn is_packed(&self) -> bool {
+        self.flags & kAudioFormatFlagIsPacked != 0
+    }
+
+    pub fn is_interleaved(&self) -> bool {
+        self.flags & kAudioFormatFlagIsNonInterleaved == 0
+    }
+
+    pub fn to_description(&self) -> AudioStreamBasicDescription {
+        AudioStreamBasicDescription {
+            mSampleRate: self.rate,
+            mFormatID: self.format_id,
+            mFormatFlags: self.flags,
+            mBytesPerPacket: self.bytes_per_packet,
+            mFramesPerPacket: self.frames_per_packet,
+            mBytesPerFrame: self.bytes_per_frame,
+            mChannelsPerFrame: self.channels,
+            mBitsPerChannel: self.bits_per_channel,
+            mReserved: 0,
+        }
+    }
+}
+
+impl<'a> From<&'a AudioStreamBasicDescription> for StreamFormat {
+    fn from(description: &'a AudioStreamBasicDescription) -> Self {
+        StreamFormat {
+            rate: description.mSampleRate,
+            format_id: description.mFormatID,
+            flags: description.mFormatFlags,
+            bytes_per_packet: description.mBytesPerPacket,
+            frames_per_packet: description.mFramesPerPacket,
+            bytes_per_frame: description.mBytesPerFrame,
+            channels: description.mChannelsPerFrame,
+            bits_per_channel: description.mBitsPerChannel,
+        }
+    }
+}
+
+// A format supported by the stream within a range of sample rates. The `rate`
+// in the `format` is set to `0.0` when any rate in the `rate_range` works.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamFormatRange {
+    pub format: StreamFormat,
+    pub rate_range: (f64, f64),
+}
+
+impl StreamFormatRange {
+    // The `rate` of the given format is ignored when it's `0.0`.
+    pub fn contains(&self, format: &StreamFormat) -> bool {
+        let rate_matched = format.rate == 0.0
+            || (format.rate >= self.rate_range.0 && format.rate <= self.rate_range.1);
+        let others_matched = StreamFormat {
+            rate: self.format.rate,
+            ..format.clone()
+        } == self.format;
+        rate_matched && others_matched
+    }
+}
+
+impl<'a> From<&'a AudioStreamRangedDescription> for StreamFormatRange {
+    fn from(description: &'a AudioStreamRangedDescription) -> Self {
+        StreamFormatRange {
+            format: StreamFormat::from(&description.mFormat),
+            rate_range: (
+                description.mSampleRateRange.mMinimum,
+                description.mSampleRateRange.mMaximum,
+            ),
+        }
+    }
+}
+
+// AudioStream
+// ============================================================================
+// The physical format is the format of the data the device actually works
+// with, while the virtual format is the one presented to the clients.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioStream(AudioStreamID); // AudioStreamID is AudioObjectID
+
+impl AudioStream {
+    pub fn new(id: AudioStreamID) -> Self {
+        AudioStream(id)
+    }
+
+    pub fn get_latency(&self, scope: &Scope) -> Result<u32, Error> {
+        let address = get_scope_property_address(scope, Property::StreamLatency);
+        self.get_property_data(&address)
+    }
+
+    pub fn get_direction(&self) -> Result<Scope, Error> {
+        let address = get_global_property_address(Property::StreamDirection);
+        // 0 means output and 1 means input.
+        let direction: u32 = self.get_property_data(&address)?;
+        Ok(if direction == 0 {
+            Scope::Output
+        } else {
+            Scope::Input
+        })
+    }
+
+    pub fn get_terminal_type(&self) -> Result<TerminalType, Error> {
+        let address = get_global_property_address(Property::StreamTerminalType);
+        let terminal_type: u32 = self.get_property_data(&address)?;
+        Ok(TerminalType::from(terminal_type))
+    }
+
+    // The first channel of the device this stream represents. The channels
+    // of the device are numbered from 1.
+    pub fn get_starting_channel(&self) -> Result<u32, Error> {
+        let address = get_global_property_address(Property::StreamStartingChannel);
+        self.get_property_data(&address)
+    }
+
+    pub fn get_virtual_format(&self) -> Result<StreamFormat, Error> {
+        self.get_format(Property::StreamVirtualFormat)
+    }
+
+    pub fn get_available_virtual_formats(&self) -> Result<Vec<StreamFormatRange>, Error> {
+        self.get_available_formats(Property::StreamAvailableVirtualFormats)
+    }
+
+    pub fn get_physical_format(&self) -> Result<StreamFormat, Error> {
+        self.get_format(Property::StreamPhysicalFormat)
+    }
+
+    pub fn get_available_physical_formats(&self) -> Result<Vec<StreamFormatRange>, Error> {
+        self.get_available_formats(Property::StreamAvailablePhysicalFormats)
+    }
+
+    pub fn is_physical_format_supported(&self, format: &StreamFormat) -> Result<bool, Error> {
+        let ranges = self.get_available_physical_formats()?;
+        Ok(ranges.iter().any(|range| range.contains(format)))
+    }
+
+    // The format change is asynchronous. The new format may not be applied
+    // immediately after this call returns.
+    pub fn set_physical_format(&self, format: &StreamFormat) -> Result<(), Error> {
+        if !self.is_physical_format_supported(format)? {
+            return Err(Error::UnsupportedFormat);
+        }
+        let address = get_global_property_address(Property::StreamPhysicalFormat);
+        self.set_property_data(&address, &format.to_description())
+    }
+
+    fn get_format(&self, property: Property) -> Result<StreamFormat, Error> {
+        let address = get_global_property_address(property);
+        let description: AudioStreamBasicDescription = self.get_property_data(&address)?;
+        Ok(StreamFormat::from(&description))
+    }
+
+    fn get_available_formats(&self, property: Property) -> Result<Vec<StreamFormatRange>, Error> {
+        let address = get_global_property_address(property);
+        let descriptions: Vec<AudioStreamRangedDescription> = self.get_property_array(&address)?;
+        Ok(descriptions.iter().map(StreamFormatRange::from).collect())
+    }
+}
+
+impl GetObjectId for AudioStream {
+    fn get_id(&self) -> AudioObjectID {
+        self.0
+    }
+}
+
+impl GetPropertyData for AudioStream {}
+impl GetPropertyArray for AudioStream {}
+impl SetPropertyData for AudioStream {}
@@ -0,0 +1,211 @@
+extern crate coreaudio_sys;
+
+use self::coreaudio_sys::{
+    AudioChannelBitmap, AudioChannelDescription, AudioChannelLayout, AudioChannelLayoutTag,
+};
+
+use std::mem; // For mem::size_of()
+use std::ptr; // For ptr::read_unaligned()
+
+// The bit of `kAudioChannelBit_Left` ... `kAudioChannelBit_TopBackRight` in the
+// channel bitmap maps to the `kAudioChannelLabel_Left` ...
+// `kAudioChannelLabel_TopBackRight`, whose values are 1 ... 18.
+const CHANNEL_BITMAP_BITS: u32 = 18;
+
+// The `kAudioChannelLabel_Discrete_N` is `(1 << 16) | N`.
+const CHANNEL_LABEL_DISCRETE_MASK: u32 = 1 << 16;
+
+// The lower 16 bits of the layout tag is the number of channels.
+const CHANNEL_LAYOUT_TAG_CHANNELS_MASK: u32 = 0xFFFF;
+
+// ChannelLabel
+// ============================================================================
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChannelLabel {
+    Unknown,
+    Unused,
+    UseCoordinates,
+    Left,
+    Right,
+    Center,
+    LFE,
+    LeftSurround,
+    RightSurround,
+    LeftCenter,
+    RightCenter,
+    CenterSurround,
+    LeftSurroundDirect,
+    RightSurroundDirect,
+    TopCenterSurround,
+    VerticalHeightLeft,
+    VerticalHeightCenter,
+    VerticalHeightRight,
+    TopBackLeft,
+    TopBackCenter,
+    TopBackRight,
+    RearSurroundLeft,
+    RearSurroundRight,
+    Mono,
+    // The N-th discrete channel, from `kAudioChannelLabel_Discrete_N`.
+    Discrete(u32),
+    Other(u32),
+}
+
+// Using the `coreaudio_sys` prefix to match the constants. Otherwise they will
+// be new variables introduced in the `match` block.
+impl From<u32> for ChannelLabel {
+    fn from(label: u32) -> Self {
+        match label {
+            coreaudio_sys::kAudioChannelLabel_Unknown => ChannelLabel::Unknown,
+            coreaudio_sys::kAudioChannelLabel_Unused => ChannelLabel::Unused,
+            coreaudio_sys::kAudioChannelLabel_UseCoordinates => ChannelLabel::UseCoordinates,
+            coreaudio_sys::kAudioChannelLabel_Left => ChannelLabel::Left,
+            coreaudio_sys::kAudioChannelLabel_Right => ChannelLabel::Right,
+            coreaudio_sys::kAudioChannelLabel_Center => ChannelLabel::Center,
+            coreaudio_sys::kAudioChannelLabel_LFEScreen => ChannelLabel::LFE,
+            coreaudio_sys::kAudioChannelLabel_LeftSurround => ChannelLabel::LeftSurround,
+            coreaudio_sys::kAudioChannelLabel_RightSurround => ChannelLabel::RightSurround,
+            coreaudio_sys::kAudioChannelLabel_LeftCenter => ChannelLabel::LeftCenter,
+            coreaudio_sys::kAudioChannelLabel_RightCenter => ChannelLabel::RightCenter,
+            coreaudio_sys::kAudioChannelLabel_CenterSurround => ChannelLabel::CenterSurround,
+            coreaudio_sys::kAudioChannelLabel_LeftSurroundDirect => {
+                ChannelLabel::LeftSurroundDirect
+            }
+            coreaudio_sys::kAudioChannelLabel_RightSurroundDirect => {
+                ChannelLabel::RightSurroundDirect
+            }
+            coreaudio_sys::kAudioChannelLabel_TopCenterSurround => ChannelLabel::TopCenterSurround,
+            coreaudio_sys::kAudioChannelLabel_VerticalHeightLeft => {
+                ChannelLabel::VerticalHeightLeft
+            }
+            coreaudio_sys::kAudioChannelLabel_VerticalHeightCenter => {
+                ChannelLabel::VerticalHeightCenter
+            }
+            coreaudio_sys::kAudioChannelLabel_VerticalHeightRight => {
+                ChannelLabel::VerticalHeightRight
+            }
+            coreaudio_sys::kAudioChannelLabel_TopBackLeft => ChannelLabel::TopBackLeft,
+            coreaudio_sys::kAudioChannelLabel_TopBackCenter => ChannelLabel::TopBackCenter,
+            coreaudio_sys::kAudioChannelLabel_TopBackRight => ChannelLabel::TopBackRight,
+            coreaudio_sys::kAudioChannelLabel_RearSurroundLeft => ChannelLabel::RearSurroundLeft,
+            coreaudio_sys::kAudioChannelLabel_RearSurroundRight => ChannelLabel::RearSurroundRight,
+            coreaudio_sys::kAudioChannelLabel_Mono => ChannelLabel::Mono,
+            l if l & CHANNEL_LABEL_DISCRETE_MASK != 0 && l >> 17 == 0 => {
+                ChannelLabel::Discrete(l & !CHANNEL_LABEL_DISCRETE_MASK)
+            }
+            l => ChannelLabel::Other(l),
+        }
+    }
+}
+
+// ChannelDescription
+// ============================================================================
+// The `coordinates` are only meaningful when the `label` is `UseCoordinates`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelDescription {
+    pub label: ChannelLabel,
+    pub flags: u32,
+    pub coordinates: [f32; 3],
+}
+
+impl<'a> From<&'a AudioChannelDescription> for ChannelDescription {
+    fn from(description: &'a AudioChannelDescription) -> Self {
+        ChannelDescription {
+            label: ChannelLabel::from(description.mChannelLabel),
+            flags: description.mChannelFlags,
+            coordinates: description.mCoordinates,
+        }
+    }
+}
+
+// ChannelLayout
+// ============================================================================
+// The `AudioChannelLayout` is described by one of the following ways:
+// 1. a predefined layout tag (e.g., `kAudioChannelLayoutTag_Stereo`),
+// 2. a bitmap of the channels, which is decoded into the labels here,
+// 3. a list of the channel descriptions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChannelLayout {
+    Tag(AudioChannelLayoutTag),
+    Bitmap(Vec<ChannelLabel>),
+    Descriptions(Vec<ChannelDescription>),
+}
+
+impl ChannelLayout {
+    pub fn channel_count(&self) -> u32 {
+        match self {
+            ChannelLayout::Tag(tag) => tag & CHANNEL_LAYOUT_TAG_CHANNELS_MASK,
+            ChannelLayout::Bitmap(labels) => labels.len() as u32,
+            ChannelLayout::Descriptions(descriptions) => descriptions.len() as u32,
+        }
+    }
+
+    // Return `None` if the layout is described by a predefined tag, since the
+    // labels of a tag cannot be derived without the AudioToolbox framework.
+    pub fn get_labels(&self) -> Option<Vec<ChannelLabel>> {
+        match self {
+            ChannelLayout::Tag(_) => None,
+            ChannelLayout::Bitmap(labels) => Some(labels.clone()),
+            ChannelLayout::Descriptions(descriptions) => {
+                Some(descriptions.iter().map(|d| d.label.clone()).collect())
+            }
+        }
+    }
+}
+
+// The `buffer` must contain a complete `AudioChannelLayout`, whose size is
+// variable and depends on the number of the channel descriptions. The buffer
+// of bytes may not be aligned for the `AudioChannelLayout`, so the layout and
+// its descriptions are copied out by unaligned reads.
+pub fn to_channel_layout(buffer: &[u8]) -> Option<ChannelLayout> {
+    if buffer.len() < mem::size_of::<AudioChannelLayout>() {
+        return None;
+    }
+    let layout: AudioChannelLayout =
+        unsafe { ptr::read_unaligned(buffer.as_ptr() as *const AudioChannelLayout) };
+    let count = layout.mNumberChannelDescriptions as usize;
+    let header_size =
+        mem::size_of::<AudioChannelLayout>() - mem::size_of::<AudioChannelDescription>();
+    if buffer.len() < header_size + count * mem::size_of::<AudioChannelDescription>() {
+        return None;
+    }
+    let first = buffer[header_size..].as_ptr() as *const AudioChannelDescription;
+    let descriptions: Vec<AudioChannelDescription> = (0..count)
+        .map(|i| unsafe { ptr::read_unaligned(first.add(i)) })
+        .collect();
+    Some(decode_channel_layout(
+        layout.mChannelLayoutTag,
+        layout.mChannelBitmap,
+        &descriptions,
+    ))
+}
+
+// Private utils
+// ============================================================================
+fn decode_channel_layout(
+    tag: AudioChannelLayoutTag,
+    bitmap: AudioChannelBitmap,
+    descriptions: &[AudioChannelDescription],
+) -> ChannelLayout {
+    match tag {
+        coreaudio_sys::kAudioChannelLayoutTag_UseChannelDescriptions => {
+            ChannelLayout::Descriptions(descriptions.iter().map(ChannelDescription::from).collect())
+        }
+        coreaudio_sys::kAudioChannelLayoutTag_UseChannelBitmap => {
+            ChannelLayout::Bitmap(decode_channel_bitmap(bitmap))
+        }
+        t => ChannelLayout::Tag(t),
+    }
+}
+
+fn decode_channel_bitmap(bitmap: AudioChannelBitmap) -> Vec<ChannelLabel> {
+    (0..CHANNEL_BITMAP_BITS)
+        .filter(|bit| bitmap & (1 << bit) != 0)
+        .map(|bit| ChannelLabel::from(bit + 1))
+        .collect()
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
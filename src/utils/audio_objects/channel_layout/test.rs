@@ -0,0 +1,163 @@
+use super::*;
+use std::slice;
+
+fn get_fake_description(label: u32) -> AudioChannelDescription {
+    AudioChannelDescription {
+        mChannelLabel: label,
+        mChannelFlags: 0,
+        mCoordinates: [0.0; 3],
+    }
+}
+
+// Tests for Public Functions
+// ============================================================================
+
+// ChannelLabel
+// ------------------------------------
+#[test]
+fn test_channel_label() {
+    assert_eq!(
+        ChannelLabel::from(coreaudio_sys::kAudioChannelLabel_Left),
+        ChannelLabel::Left
+    );
+    assert_eq!(
+        ChannelLabel::from(coreaudio_sys::kAudioChannelLabel_LFEScreen),
+        ChannelLabel::LFE
+    );
+    assert_eq!(
+        ChannelLabel::from(coreaudio_sys::kAudioChannelLabel_Discrete_0 | 5),
+        ChannelLabel::Discrete(5)
+    );
+    assert_eq!(
+        ChannelLabel::from(coreaudio_sys::kAudioChannelLabel_Discrete),
+        ChannelLabel::Other(coreaudio_sys::kAudioChannelLabel_Discrete)
+    );
+}
+
+// ChannelLayout
+// ------------------------------------
+#[test]
+fn test_channel_layout_channel_count() {
+    let stereo = ChannelLayout::Tag(coreaudio_sys::kAudioChannelLayoutTag_Stereo);
+    assert_eq!(stereo.channel_count(), 2);
+    assert!(stereo.get_labels().is_none());
+}
+
+// to_channel_layout
+// ------------------------------------
+#[test]
+fn test_to_channel_layout_with_small_buffer() {
+    let buffer = vec![0_u8; 4];
+    assert!(to_channel_layout(&buffer).is_none());
+}
+
+#[test]
+fn test_to_channel_layout() {
+    let mut layout = AudioChannelLayout::default();
+    layout.mChannelLayoutTag = coreaudio_sys::kAudioChannelLayoutTag_UseChannelDescriptions;
+    layout.mNumberChannelDescriptions = 1;
+    layout.mChannelDescriptions[0] = get_fake_description(coreaudio_sys::kAudioChannelLabel_Mono);
+    let buffer = unsafe {
+        slice::from_raw_parts(
+            &layout as *const AudioChannelLayout as *const u8,
+            mem::size_of::<AudioChannelLayout>(),
+        )
+    };
+    assert_eq!(
+        to_channel_layout(buffer).unwrap().get_labels().unwrap(),
+        vec![ChannelLabel::Mono]
+    );
+
+    // The buffer is too small to contain two channel descriptions.
+    layout.mNumberChannelDescriptions = 2;
+    let buffer = unsafe {
+        slice::from_raw_parts(
+            &layout as *const AudioChannelLayout as *const u8,
+            mem::size_of::<AudioChannelLayout>(),
+        )
+    };
+    assert!(to_channel_layout(buffer).is_none());
+}
+
+#[test]
+fn test_to_channel_layout_with_unaligned_buffer() {
+    let mut layout = AudioChannelLayout::default();
+    layout.mChannelLayoutTag = coreaudio_sys::kAudioChannelLayoutTag_UseChannelDescriptions;
+    layout.mNumberChannelDescriptions = 1;
+    layout.mChannelDescriptions[0] = get_fake_description(coreaudio_sys::kAudioChannelLabel_Left);
+    let bytes = unsafe {
+        slice::from_raw_parts(
+            &layout as *const AudioChannelLayout as *const u8,
+            mem::size_of::<AudioChannelLayout>(),
+        )
+    };
+    // Put the layout at an odd address.
+    let mut buffer = vec![0_u8; 1];
+    buffer.extend_from_slice(bytes);
+    assert_eq!(
+        to_channel_layout(&buffer[1..])
+            .unwrap()
+            .get_labels()
+            .unwrap(),
+        vec![ChannelLabel::Left]
+    );
+}
+
+// Tests for Private Functions
+// ============================================================================
+
+// decode_channel_layout
+// ------------------------------------
+#[test]
+fn test_decode_channel_layout_with_descriptions() {
+    let descriptions = vec![
+        get_fake_description(coreaudio_sys::kAudioChannelLabel_Left),
+        get_fake_description(coreaudio_sys::kAudioChannelLabel_Right),
+        get_fake_description(coreaudio_sys::kAudioChannelLabel_Center),
+        get_fake_description(coreaudio_sys::kAudioChannelLabel_LFEScreen),
+    ];
+    let layout = decode_channel_layout(
+        coreaudio_sys::kAudioChannelLayoutTag_UseChannelDescriptions,
+        0,
+        &descriptions,
+    );
+    assert_eq!(layout.channel_count(), 4);
+    assert_eq!(
+        layout.get_labels().unwrap(),
+        vec![
+            ChannelLabel::Left,
+            ChannelLabel::Right,
+            ChannelLabel::Center,
+            ChannelLabel::LFE
+        ]
+    );
+}
+
+#[test]
+fn test_decode_channel_layout_with_bitmap() {
+    // kAudioChannelBit_Left | kAudioChannelBit_Right | kAudioChannelBit_LFEScreen
+    let bitmap = 1 << 0 | 1 << 1 | 1 << 3;
+    let layout = decode_channel_layout(
+        coreaudio_sys::kAudioChannelLayoutTag_UseChannelBitmap,
+        bitmap,
+        &[],
+    );
+    assert_eq!(
+        layout,
+        ChannelLayout::Bitmap(vec![
+            ChannelLabel::Left,
+            ChannelLabel::Right,
+            ChannelLabel::LFE
+        ])
+    );
+}
+
+#[test]
+fn test_decode_channel_layout_with_tag() {
+    let layout = decode_channel_layout(coreaudio_sys::kAudioChannelLayoutTag_Mono, 0, &[]);
+    assert_eq!(
+        layout,
+        ChannelLayout::Tag(coreaudio_sys::kAudioChannelLayoutTag_Mono)
+    );
+    assert_eq!(layout.channel_count(), 1);
+}
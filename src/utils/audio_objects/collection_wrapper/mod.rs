@@ -0,0 +1,258 @@
+extern crate core_foundation_sys;
+
+use self::core_foundation_sys::array::{
+    kCFTypeArrayCallBacks, CFArrayCreate, CFArrayGetCount, CFArrayGetValueAtIndex, CFArrayRef,
+};
+use self::core_foundation_sys::base::{
+    kCFAllocatorDefault, CFGetTypeID, CFIndex, CFRelease, CFRetain, CFTypeRef,
+};
+use self::core_foundation_sys::dictionary::{
+    kCFTypeDictionaryKeyCallBacks, kCFTypeDictionaryValueCallBacks, CFDictionaryCreate,
+    CFDictionaryRef,
+};
+use self::core_foundation_sys::number::{kCFNumberSInt32Type, CFNumberCreate};
+use self::core_foundation_sys::string::{CFStringGetTypeID, CFStringRef};
+use super::string_wrapper::{self, StringRef};
+use std::fmt; // For fmt::{Debug, Formatter, Result}
+use std::mem; // For mem::forget()
+use std::os::raw::c_void;
+use std::ptr; // For ptr::null()
+
+// Using PartialEq for comparison.
+#[derive(PartialEq)]
+pub enum Error {
+    CreationFailed,
+    NullReference,
+    String(string_wrapper::Error),
+    WrongType,
+}
+
+// To convert an string_wrapper::Error to a Error.
+impl From<string_wrapper::Error> for Error {
+    fn from(e: string_wrapper::Error) -> Self {
+        Error::String(e)
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printable = match self {
+            Error::CreationFailed => "Fail to create the collection.".to_string(),
+            Error::NullReference => "The inner reference of the collection is null.".to_string(),
+            Error::String(e) => format!("Fail to handle the string element: {:?}", e),
+            Error::WrongType => "The element type is not the expected one.".to_string(),
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+// The values can be put into an ArrayRef or a DictionaryRef.
+pub enum Value {
+    Array(ArrayRef),
+    Dictionary(DictionaryRef),
+    Number(i32),
+    String(StringRef),
+}
+
+impl<'a> From<&'a str> for Value {
+    fn from(s: &'a str) -> Self {
+        Value::String(StringRef::from(s))
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Number(b as i32)
+    }
+}
+
+// Public APIs
+// ============================================================================
+pub struct ArrayRef(CFArrayRef);
+impl ArrayRef {
+    pub fn new(array_ref: CFArrayRef) -> Self {
+        // To allow user to create a empty null array, we don't check if
+        // array_ref is null or not.
+        ArrayRef(array_ref)
+    }
+
+    pub fn from_values(values: Vec<Value>) -> Result<Self, Error> {
+        let elements = to_type_refs(values)?;
+        let raw_elements: Vec<CFTypeRef> = elements.iter().map(|e| e.0).collect();
+        let array_ref = unsafe {
+            CFArrayCreate(
+                kCFAllocatorDefault,
+                raw_elements.as_ptr(),
+                raw_elements.len() as CFIndex,
+                &kCFTypeArrayCallBacks,
+            )
+        };
+        // The array retains its elements, so it's fine to release `elements`
+        // after leaving this function.
+        if array_ref.is_null() {
+            Err(Error::CreationFailed)
+        } else {
+            Ok(ArrayRef::new(array_ref))
+        }
+    }
+
+    pub fn from_strings(strings: &[&str]) -> Result<Self, Error> {
+        let values = strings.iter().map(|s| Value::from(*s)).collect();
+        Self::from_values(values)
+    }
+
+    pub fn len(&self) -> Result<usize, Error> {
+        if self.0.is_null() {
+            return Err(Error::NullReference);
+        }
+        Ok(unsafe { CFArrayGetCount(self.0) } as usize)
+    }
+
+    pub fn to_strings(&self) -> Result<Vec<String>, Error> {
+        let mut strings = Vec::new();
+        for index in 0..self.len()? {
+            let element = unsafe { CFArrayGetValueAtIndex(self.0, index as CFIndex) };
+            if element.is_null() || unsafe { CFGetTypeID(element) != CFStringGetTypeID() } {
+                return Err(Error::WrongType);
+            }
+            // The element follows the *Get Rule*. Retain it so it can be
+            // released when the StringRef is dropped.
+            let string_ref = StringRef::new(unsafe { CFRetain(element) } as CFStringRef);
+            strings.push(string_ref.into_string()?);
+        }
+        Ok(strings)
+    }
+
+    // Hand the ownership of the inner reference over to the caller. The
+    // caller is responsible for releasing it.
+    pub fn into_raw(self) -> CFArrayRef {
+        let array_ref = self.0;
+        mem::forget(self);
+        array_ref
+    }
+}
+
+impl Drop for ArrayRef {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { CFRelease(self.0 as CFTypeRef) };
+        }
+    }
+}
+
+impl Default for ArrayRef {
+    fn default() -> Self {
+        ArrayRef::new(ptr::null())
+    }
+}
+
+pub struct DictionaryRef(CFDictionaryRef);
+impl DictionaryRef {
+    pub fn new(dictionary_ref: CFDictionaryRef) -> Self {
+        // To allow user to create a empty null dictionary, we don't check if
+        // dictionary_ref is null or not.
+        DictionaryRef(dictionary_ref)
+    }
+
+    pub fn from_pairs(pairs: Vec<(&str, Value)>) -> Result<Self, Error> {
+        let (keys, values): (Vec<&str>, Vec<Value>) = pairs.into_iter().unzip();
+        let keys = to_type_refs(keys.into_iter().map(Value::from).collect())?;
+        let values = to_type_refs(values)?;
+        let raw_keys: Vec<CFTypeRef> = keys.iter().map(|k| k.0).collect();
+        let raw_values: Vec<CFTypeRef> = values.iter().map(|v| v.0).collect();
+        let dictionary_ref = unsafe {
+            CFDictionaryCreate(
+                kCFAllocatorDefault,
+                raw_keys.as_ptr(),
+                raw_values.as_ptr(),
+                raw_keys.len() as CFIndex,
+                &kCFTypeDictionaryKeyCallBacks,
+                &kCFTypeDictionaryValueCallBacks,
+            )
+        };
+        // The dictionary retains its keys and values, so it's fine to release
+        // `keys` and `values` after leaving this function.
+        if dictionary_ref.is_null() {
+            Err(Error::CreationFailed)
+        } else {
+            Ok(DictionaryRef::new(dictionary_ref))
+        }
+    }
+
+    pub fn get_raw(&self) -> CFDictionaryRef {
+        self.0
+    }
+
+    // Hand the ownership of the inner reference over to the caller. The
+    // caller is responsible for releasing it.
+    pub fn into_raw(self) -> CFDictionaryRef {
+        let dictionary_ref = self.0;
+        mem::forget(self);
+        dictionary_ref
+    }
+}
+
+impl Drop for DictionaryRef {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { CFRelease(self.0 as CFTypeRef) };
+        }
+    }
+}
+
+impl Default for DictionaryRef {
+    fn default() -> Self {
+        DictionaryRef::new(ptr::null())
+    }
+}
+
+// Private utils
+// ============================================================================
+// An owned CFTypeRef that will be released when it's dropped.
+struct TypeRef(CFTypeRef);
+
+impl Drop for TypeRef {
+    fn drop(&mut self) {
+        assert!(!self.0.is_null());
+        unsafe { CFRelease(self.0) };
+    }
+}
+
+fn to_type_refs(values: Vec<Value>) -> Result<Vec<TypeRef>, Error> {
+    let mut type_refs = Vec::with_capacity(values.len());
+    for value in values {
+        type_refs.push(to_type_ref(value)?);
+    }
+    Ok(type_refs)
+}
+
+fn to_type_ref(value: Value) -> Result<TypeRef, Error> {
+    let type_ref = match value {
+        Value::Array(array) => array.into_raw() as CFTypeRef,
+        Value::Dictionary(dictionary) => dictionary.into_raw() as CFTypeRef,
+        Value::Number(number) => unsafe {
+            CFNumberCreate(
+                kCFAllocatorDefault,
+                kCFNumberSInt32Type,
+                &number as *const i32 as *const c_void,
+            ) as CFTypeRef
+        },
+        Value::String(string) => string.into_raw() as CFTypeRef,
+    };
+    if type_ref.is_null() {
+        Err(Error::NullReference)
+    } else {
+        Ok(TypeRef(type_ref))
+    }
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
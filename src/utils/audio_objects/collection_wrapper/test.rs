@@ -0,0 +1,52 @@
+use super::*;
+
+// Tests for Public Functions
+// ============================================================================
+
+// ArrayRef
+// ------------------------------------
+#[test]
+fn test_array_with_null_reference() {
+    let array = ArrayRef::default();
+    assert_eq!(array.len().unwrap_err(), Error::NullReference);
+    assert_eq!(array.to_strings().unwrap_err(), Error::NullReference);
+}
+
+#[test]
+fn test_array_from_strings() {
+    let expected = vec!["BuiltInMicrophoneDevice", "BuiltInSpeakerDevice"];
+    let array = ArrayRef::from_strings(&expected).unwrap();
+    assert_eq!(array.len().unwrap(), expected.len());
+    assert_eq!(array.to_strings().unwrap(), expected);
+}
+
+#[test]
+fn test_array_from_values_with_wrong_type() {
+    let array = ArrayRef::from_values(vec![Value::from("uid"), Value::from(1)]).unwrap();
+    assert_eq!(array.len().unwrap(), 2);
+    assert_eq!(array.to_strings().unwrap_err(), Error::WrongType);
+}
+
+// DictionaryRef
+// ------------------------------------
+#[test]
+fn test_dictionary_from_pairs() {
+    let sub_devices = ArrayRef::from_strings(&["BuiltInSpeakerDevice"]).unwrap();
+    let dictionary = DictionaryRef::from_pairs(vec![
+        ("name", Value::from("Aggregate Device")),
+        ("private", Value::from(true)),
+        ("subdevices", Value::Array(sub_devices)),
+    ])
+    .unwrap();
+    assert!(!dictionary.get_raw().is_null());
+}
+
+// Tests for Private Functions
+// ============================================================================
+
+// to_type_ref
+// ------------------------------------
+#[test]
+fn test_to_type_ref_with_null_string() {
+    assert!(to_type_ref(Value::String(StringRef::default())).is_err());
+}
@@ -0,0 +1,138 @@
+use super::{AudioObject, Scope, TransportType};
+
+// DeviceQuery
+// ============================================================================
+// The criteria to select devices. A device matches the query only if it meets
+// all the given criteria. Failing to get any property of a device that is
+// required by the query means the device does not match.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeviceQuery {
+    scope: Scope,
+    min_channels: Option<u32>,
+    rate: Option<f64>,
+    transport_types: Vec<TransportType>,
+    name_pattern: Option<String>,
+    uid_pattern: Option<String>,
+    manufacturer_pattern: Option<String>,
+}
+
+impl DeviceQuery {
+    pub fn new(scope: Scope) -> Self {
+        DeviceQuery {
+            scope,
+            min_channels: None,
+            rate: None,
+            transport_types: Vec::new(),
+            name_pattern: None,
+            uid_pattern: None,
+            manufacturer_pattern: None,
+        }
+    }
+
+    pub fn min_channels(mut self, channels: u32) -> Self {
+        self.min_channels = Some(channels);
+        self
+    }
+
+    pub fn supports_rate(mut self, rate: f64) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    // Calling this several times matches any of the given transport types.
+    pub fn transport_type(mut self, transport_type: TransportType) -> Self {
+        self.transport_types.push(transport_type);
+        self
+    }
+
+    // The patterns below match any value containing the pattern, ignoring
+    // the case.
+    pub fn name(mut self, pattern: &str) -> Self {
+        self.name_pattern = Some(pattern.to_string());
+        self
+    }
+
+    pub fn uid(mut self, pattern: &str) -> Self {
+        self.uid_pattern = Some(pattern.to_string());
+        self
+    }
+
+    pub fn manufacturer(mut self, pattern: &str) -> Self {
+        self.manufacturer_pattern = Some(pattern.to_string());
+        self
+    }
+
+    pub fn get_scope(&self) -> &Scope {
+        &self.scope
+    }
+
+    pub fn matches(&self, device: &AudioObject) -> bool {
+        self.matches_scope(device)
+            && self.matches_channels(device)
+            && self.matches_rate(device)
+            && self.matches_transport_type(device)
+            && matches_optional_pattern(&self.name_pattern, || device.get_name().ok())
+            && matches_optional_pattern(&self.uid_pattern, || device.get_uid().ok())
+            && matches_optional_pattern(&self.manufacturer_pattern, || {
+                device.get_manufacturer().ok()
+            })
+    }
+
+    fn matches_scope(&self, device: &AudioObject) -> bool {
+        device.in_scope(&self.scope).unwrap_or(false)
+    }
+
+    fn matches_channels(&self, device: &AudioObject) -> bool {
+        match self.min_channels {
+            Some(min) => device
+                .get_channel_count(&self.scope)
+                .map(|channels| channels >= min)
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+
+    fn matches_rate(&self, device: &AudioObject) -> bool {
+        match self.rate {
+            Some(rate) => device
+                .get_supported_rates(&self.scope)
+                .map(|rates| rates.supports_rate(rate))
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+
+    fn matches_transport_type(&self, device: &AudioObject) -> bool {
+        if self.transport_types.is_empty() {
+            return true;
+        }
+        device
+            .get_transport_type()
+            .map(|transport_type| self.transport_types.contains(&transport_type))
+            .unwrap_or(false)
+    }
+}
+
+// Utils
+// ============================================================================
+fn matches_optional_pattern<F>(pattern: &Option<String>, get_value: F) -> bool
+where
+    F: FnOnce() -> Option<String>,
+{
+    match *pattern {
+        Some(ref pattern) => match get_value() {
+            Some(value) => matches_pattern(&value, pattern),
+            None => false,
+        },
+        None => true,
+    }
+}
+
+fn matches_pattern(value: &str, pattern: &str) -> bool {
+    value.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
@@ -0,0 +1,65 @@
+use super::*;
+
+// Tests for Public Functions
+// ============================================================================
+
+// DeviceQuery
+// ------------------------------------
+#[test]
+fn test_device_query_builder() {
+    let query = DeviceQuery::new(Scope::Output)
+        .min_channels(2)
+        .supports_rate(48_000.0)
+        .transport_type(TransportType::USB)
+        .transport_type(TransportType::BuiltIn)
+        .name("speaker")
+        .uid("uid")
+        .manufacturer("apple");
+    assert_eq!(query.get_scope(), &Scope::Output);
+    assert_eq!(query.min_channels, Some(2));
+    assert_eq!(query.rate, Some(48_000.0));
+    assert_eq!(
+        query.transport_types,
+        vec![TransportType::USB, TransportType::BuiltIn]
+    );
+    assert_eq!(query.name_pattern, Some("speaker".to_string()));
+    assert_eq!(query.uid_pattern, Some("uid".to_string()));
+    assert_eq!(query.manufacturer_pattern, Some("apple".to_string()));
+}
+
+#[test]
+fn test_device_query_with_invalid_device() {
+    let unknown_device = AudioObject::default();
+    assert!(!DeviceQuery::new(Scope::Input).matches(&unknown_device));
+    assert!(!DeviceQuery::new(Scope::Output).matches(&unknown_device));
+}
+
+// Tests for Private Functions
+// ============================================================================
+
+// matches_pattern
+// ------------------------------------
+#[test]
+fn test_matches_pattern() {
+    assert!(matches_pattern("MacBook Pro Speakers", "speakers"));
+    assert!(matches_pattern("MacBook Pro Speakers", "BOOK"));
+    assert!(matches_pattern("MacBook Pro Speakers", ""));
+    assert!(!matches_pattern("MacBook Pro Speakers", "microphone"));
+}
+
+// matches_optional_pattern
+// ------------------------------------
+#[test]
+fn test_matches_optional_pattern() {
+    let pattern = Some("usb".to_string());
+    assert!(matches_optional_pattern(&pattern, || Some(
+        "USB Audio Device".to_string()
+    )));
+    assert!(!matches_optional_pattern(&pattern, || Some(
+        "Built-in Output".to_string()
+    )));
+    // Failing to get the value means no match.
+    assert!(!matches_optional_pattern(&pattern, || None));
+    // No pattern means any value matches.
+    assert!(matches_optional_pattern(&None, || None));
+}
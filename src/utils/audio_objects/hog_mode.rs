@@ -0,0 +1,69 @@
+extern crate coreaudio_sys;
+
+use self::coreaudio_sys::pid_t;
+use super::property_address::{get_global_property_address, Property};
+use super::{AudioObject, Error, GetPropertyData, SetPropertyData};
+
+use std::process;
+
+// The value of `kAudioDevicePropertyHogMode` when no process hogs the device.
+const NO_OWNER: pid_t = -1;
+
+// HogModeGuard
+// ============================================================================
+// The hog mode of the device is released when the guard is dropped, including
+// when the guard is dropped during unwinding from a panic. If the process
+// aborts or exits instead, the system releases the hog mode of the process.
+#[derive(Debug)]
+pub struct HogModeGuard(AudioObject);
+
+impl HogModeGuard {
+    // Setting `kAudioDevicePropertyHogMode` toggles the hog mode of the
+    // current process: it takes the hog mode if the device is free, and
+    // releases it if the current process holds it. The value set is ignored.
+    pub fn new(device: &AudioObject) -> Result<Self, Error> {
+        if let Some(pid) = get_hog_mode_owner(device)? {
+            return Err(Error::DeviceHogged(pid));
+        }
+        toggle_hog_mode(device)?;
+        match get_hog_mode_owner(device)? {
+            Some(pid) if pid == get_current_pid() => Ok(HogModeGuard(device.clone())),
+            Some(pid) => Err(Error::DeviceHogged(pid)),
+            None => Err(Error::DeviceHogged(NO_OWNER)),
+        }
+    }
+
+    pub fn get_device(&self) -> &AudioObject {
+        &self.0
+    }
+}
+
+impl Drop for HogModeGuard {
+    // Don't panic here since the guard may be dropped while unwinding.
+    fn drop(&mut self) {
+        if let Ok(Some(pid)) = get_hog_mode_owner(&self.0) {
+            if pid == get_current_pid() {
+                let _ = toggle_hog_mode(&self.0);
+            }
+        }
+    }
+}
+
+// Utils
+// ============================================================================
+// Return the pid of the process hogging the device, or `None` if the device is
+// available to all processes.
+pub fn get_hog_mode_owner(device: &AudioObject) -> Result<Option<pid_t>, Error> {
+    let address = get_global_property_address(Property::DeviceHogMode);
+    let pid: pid_t = device.get_property_data(&address)?;
+    Ok(if pid == NO_OWNER { None } else { Some(pid) })
+}
+
+pub fn get_current_pid() -> pid_t {
+    process::id() as pid_t
+}
+
+fn toggle_hog_mode(device: &AudioObject) -> Result<(), Error> {
+    let address = get_global_property_address(Property::DeviceHogMode);
+    device.set_property_data(&address, &get_current_pid())
+}
@@ -1,23 +1,54 @@
 extern crate coreaudio_sys;
 
+mod aggregate_device;
+mod audio_box;
+mod audio_clock_device;
 mod audio_object_utils;
+mod audio_plug_in;
+mod audio_stream;
+mod channel_layout;
+mod collection_wrapper;
+mod device_query;
+mod hog_mode;
+mod object_class;
 mod property_address;
+mod property_listener;
+mod rate_range;
 mod string_wrapper;
-
+mod transport_type;
+
+pub use self::aggregate_device::{AggregateDevice, AggregateDeviceDescription, SubDevice};
+pub use self::audio_box::AudioBox;
+pub use self::audio_clock_device::AudioClockDevice;
+pub use self::audio_plug_in::AudioPlugIn;
+pub use self::audio_stream::{AudioStream, StreamFormat, StreamFormatRange, TerminalType};
+use self::channel_layout::to_channel_layout;
+pub use self::channel_layout::{ChannelDescription, ChannelLabel, ChannelLayout};
 use self::coreaudio_sys::{
     kAudioObjectSystemObject, // AudioObjectID
     kAudioObjectUnknown,      // AudioObjectID
+    pid_t,
     AudioBuffer,
     AudioBufferList,
+    AudioClassID,
     AudioObjectID,
     AudioObjectPropertyAddress,
-    AudioStreamID, // AudioObjectID
     AudioValueRange,
     AudioValueTranslation,
 };
-use self::property_address::{get_global_property_address, get_scope_property_address, Property};
+pub use self::device_query::DeviceQuery;
+use self::hog_mode::get_hog_mode_owner;
+pub use self::hog_mode::HogModeGuard;
+pub use self::object_class::ObjectClass;
+use self::property_address::{
+    get_element_property_address, get_global_property_address, get_scope_property_address, Property,
+};
+pub use self::property_listener::PropertyListener;
+pub use self::rate_range::{RateRange, SupportedRates};
 use self::string_wrapper::StringRef;
+pub use self::transport_type::TransportType;
 
+use std::cmp::Ordering;
 use std::f64; // For f64::{MAX, MIN}
 use std::fmt; // For fmt::{Debug, Formatter, Result}
 use std::mem; // For mem::size_of()
@@ -27,7 +58,7 @@ use std::slice;
 // TODO: Maybe we should move this enum out since other module may also
 //       need the scope.
 // Using PartialEq for comparison.
-#[derive(PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Scope {
     Input,
     Output,
@@ -36,10 +67,18 @@ pub enum Scope {
 // Using PartialEq for comparison.
 #[derive(PartialEq)]
 pub enum Error {
+    CollectionFailed(collection_wrapper::Error),
     ConversionFailed(string_wrapper::Error),
+    DeviceHogged(pid_t),
+    InvalidClockSource,
+    InvalidDescription,
     InvalidParameters(audio_object_utils::Error),
+    // The device reports the given nominal rate, which isn't positive, e.g.,
+    // while the device is being reconfigured or after it's gone.
+    InvalidRate(f64),
     NoDeviceFound,
     SetSameDevice,
+    UnsupportedFormat,
     WrongScope,
 }
 
@@ -60,10 +99,16 @@ impl From<string_wrapper::Error> for Error {
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let printable = match self {
+            Error::CollectionFailed(e) => format!("Fail to handle collection: {:?}", e),
             Error::ConversionFailed(e) => format!("Fail to convert string: {:?}", e),
+            Error::DeviceHogged(pid) => format!("The device is hogged by process {}", pid),
+            Error::InvalidClockSource => "The given clock source is invalid.".to_string(),
+            Error::InvalidDescription => "The given description is invalid.".to_string(),
             Error::InvalidParameters(e) => format!("Invalid parameters: {:?}", e),
+            Error::InvalidRate(rate) => format!("The rate {} is invalid.", rate),
             Error::NoDeviceFound => "No valid device found by given information.".to_string(),
             Error::SetSameDevice => "Try setting the device with the same one".to_string(),
+            Error::UnsupportedFormat => "The given format is not supported.".to_string(),
             Error::WrongScope => "The given scope is wrong.".to_string(),
         };
         write!(f, "{}", printable)
@@ -134,6 +179,15 @@ trait GetPropertyVeriableSizedData {
     }
 }
 
+trait GetPropertyRawData {
+    fn get_property_raw_data(&self, address: &AudioObjectPropertyAddress) -> Result<Vec<u8>, Error>
+    where
+        Self: GetObjectId,
+    {
+        audio_object_utils::get_property_raw_data(self.get_id(), address).map_err(|e| e.into())
+    }
+}
+
 trait SetPropertyData {
     fn set_property_data<T>(
         &self,
@@ -174,6 +228,32 @@ impl AudioSystemObject {
         }
     }
 
+    // Call the callback every time the default device in the scope changes.
+    pub fn add_default_device_listener<F>(
+        &self,
+        scope: &Scope,
+        callback: F,
+    ) -> Result<PropertyListener, Error>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let address = get_global_property_address(if scope == &Scope::Input {
+            Property::DefaultInputDevice
+        } else {
+            Property::DefaultOutputDevice
+        });
+        PropertyListener::new(self.0, address, callback)
+    }
+
+    // Call the callback every time a device is added or removed.
+    pub fn add_devices_listener<F>(&self, callback: F) -> Result<PropertyListener, Error>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let address = get_global_property_address(Property::Devices);
+        PropertyListener::new(self.0, address, callback)
+    }
+
     // Apple has no API to get input-only or output-only devices. To do that,
     // we need to get all the devices first ans then check if they are input
     // or output one by one.
@@ -185,6 +265,43 @@ impl AudioSystemObject {
         Ok(devices)
     }
 
+    // The system object is the root of the HAL object tree. Use this to walk
+    // the tree with the `AudioObject` methods.
+    pub fn to_object(&self) -> AudioObject {
+        AudioObject::new(self.0)
+    }
+
+    pub fn get_plug_ins(&self) -> Result<Vec<AudioPlugIn>, Error> {
+        let objects = get_object_list(self, Property::PlugIns)?;
+        Ok(objects.into_iter().map(AudioPlugIn::new).collect())
+    }
+
+    pub fn get_boxes(&self) -> Result<Vec<AudioBox>, Error> {
+        let objects = get_object_list(self, Property::Boxes)?;
+        Ok(objects.into_iter().map(AudioBox::new).collect())
+    }
+
+    pub fn get_clock_devices(&self) -> Result<Vec<AudioClockDevice>, Error> {
+        let objects = get_object_list(self, Property::ClockDevices)?;
+        Ok(objects.into_iter().map(AudioClockDevice::new).collect())
+    }
+
+    // Return the devices matching the query, ordered by the given comparison.
+    // The devices compared as equal stay in the order the system reports.
+    pub fn find_devices<F>(
+        &self,
+        query: &DeviceQuery,
+        compare: F,
+    ) -> Result<Vec<AudioObject>, Error>
+    where
+        F: FnMut(&AudioObject, &AudioObject) -> Ordering,
+    {
+        let mut devices: Vec<AudioObject> = self.get_all_devices()?;
+        devices.retain(|device| query.matches(device));
+        devices.sort_by(compare);
+        Ok(devices)
+    }
+
     pub fn get_all_devices(&self) -> Result<Vec<AudioObject>, Error> {
         let address = get_global_property_address(Property::Devices);
         self.get_property_array::<AudioObject>(&address)
@@ -202,6 +319,14 @@ impl AudioSystemObject {
             return Err(Error::WrongScope);
         }
 
+        // A device hogged by another process can't be used by others. The
+        // device is taken as not hogged if it has no hog mode to read.
+        if let Ok(Some(pid)) = get_hog_mode_owner(device) {
+            if pid != hog_mode::get_current_pid() {
+                return Err(Error::DeviceHogged(pid));
+            }
+        }
+
         let default_device = self.get_default_device(scope)?;
         if device == &default_device {
             return Err(Error::SetSameDevice);
@@ -216,6 +341,37 @@ impl AudioSystemObject {
         self.set_property_data(&address, device.into())
             .map_err(|e| e.into())
     }
+
+    // The clock master must be one of the sub-devices. The created device will
+    // be destroyed once the returned handle is dropped.
+    pub fn create_aggregate_device(
+        &self,
+        description: &AggregateDeviceDescription,
+    ) -> Result<AggregateDevice, Error> {
+        AggregateDevice::new(description)
+    }
+
+    // Group all the devices by their clock domains. The devices in the same
+    // group share the same clock, so they can be aggregated without drift
+    // compensation. A device whose clock domain is unknown (`0`) or cannot be
+    // queried is put into a group of its own with the domain `0`.
+    pub fn get_devices_by_clock_domain(&self) -> Result<Vec<ClockDomain>, Error> {
+        let mut domains: Vec<ClockDomain> = Vec::new();
+        for device in self.get_all_devices()? {
+            let id = device.get_clock_domain().unwrap_or(0);
+            if id != 0 {
+                if let Some(domain) = domains.iter_mut().find(|d| d.id == id) {
+                    domain.devices.push(device);
+                    continue;
+                }
+            }
+            domains.push(ClockDomain {
+                id,
+                devices: vec![device],
+            });
+        }
+        Ok(domains)
+    }
 }
 
 impl GetObjectId for AudioSystemObject {
@@ -228,6 +384,57 @@ impl GetPropertyData for AudioSystemObject {}
 impl SetPropertyData for AudioSystemObject {}
 impl GetPropertyArray for AudioSystemObject {}
 
+// Clock
+// ============================================================================
+// A clock source of a device, e.g., internal, word clock or S/PDIF.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClockSource {
+    pub id: u32,
+    pub name: String,
+}
+
+// The devices sharing the same clock.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClockDomain {
+    pub id: u32,
+    pub devices: Vec<AudioObject>,
+}
+
+// Latency
+// ============================================================================
+// The latency components of a stream on a device, in frames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Latency {
+    pub device: u32,
+    pub stream: u32,
+    pub safety_offset: u32,
+    pub buffer_frame_size: u32,
+}
+
+impl Latency {
+    pub fn new(device: u32, stream: u32, safety_offset: u32, buffer_frame_size: u32) -> Self {
+        Latency {
+            device,
+            stream,
+            safety_offset,
+            buffer_frame_size,
+        }
+    }
+
+    pub fn get_frames(&self) -> u32 {
+        self.device + self.stream + self.safety_offset + self.buffer_frame_size
+    }
+
+    // Return `None` if the rate isn't positive.
+    pub fn to_seconds(&self, rate: f64) -> Option<f64> {
+        if rate > 0.0 {
+            Some(f64::from(self.get_frames()) / rate)
+        } else {
+            None
+        }
+    }
+}
+
 // AudioObject
 // ============================================================================
 #[derive(Clone, Debug, PartialEq)]
@@ -262,6 +469,146 @@ impl AudioObject {
         Ok(count)
     }
 
+    pub fn get_preferred_channel_layout(&self, scope: &Scope) -> Result<ChannelLayout, Error> {
+        let address = get_scope_property_address(scope, Property::DevicePreferredChannelLayout);
+        let buffer = self.get_property_raw_data(&address)?;
+        to_channel_layout(&buffer).ok_or(Error::InvalidParameters(
+            audio_object_utils::Error::BadPropertySize,
+        ))
+    }
+
+    // The channels are numbered from 1.
+    pub fn get_preferred_stereo_channels(&self, scope: &Scope) -> Result<(u32, u32), Error> {
+        let address = get_scope_property_address(scope, Property::DevicePreferredChannelsForStereo);
+        let channels: [u32; 2] = self.get_property_data(&address)?;
+        Ok((channels[0], channels[1]))
+    }
+
+    // The channels are numbered from 1. The channel 0 is the master element.
+    pub fn get_channel_name(&self, scope: &Scope, channel: u32) -> Result<String, Error> {
+        let address = get_element_property_address(scope, Property::ElementName, channel);
+        let name: StringRef = self.get_property_data(&address)?;
+        name.into_string().map_err(Error::ConversionFailed)
+    }
+
+    // The name will be an empty string if the channel has no name.
+    pub fn get_channel_names(&self, scope: &Scope) -> Result<Vec<String>, Error> {
+        let mut names = Vec::new();
+        for channel in 1..=self.get_channel_count(scope)? {
+            let name = match self.get_channel_name(scope, channel) {
+                Ok(name) => name,
+                Err(Error::ConversionFailed(string_wrapper::Error::LengthIsZero))
+                | Err(Error::InvalidParameters(audio_object_utils::Error::UnknownProperty)) => {
+                    String::new()
+                }
+                Err(e) => return Err(e),
+            };
+            names.push(name);
+        }
+        Ok(names)
+    }
+
+    // Return an empty list if the device has no selectable clock source.
+    pub fn get_clock_sources(&self, scope: &Scope) -> Result<Vec<ClockSource>, Error> {
+        let address = get_scope_property_address(scope, Property::DeviceClockSources);
+        let ids: Vec<u32> = match self.get_property_array(&address) {
+            Ok(ids) => ids,
+            Err(Error::InvalidParameters(audio_object_utils::Error::SizeIsZero))
+            | Err(Error::InvalidParameters(audio_object_utils::Error::UnknownProperty)) => {
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e),
+        };
+        let mut sources = Vec::new();
+        for id in ids {
+            let name = self.get_clock_source_name(scope, id)?;
+            sources.push(ClockSource { id, name });
+        }
+        Ok(sources)
+    }
+
+    pub fn get_clock_source(&self, scope: &Scope) -> Result<ClockSource, Error> {
+        let address = get_scope_property_address(scope, Property::DeviceClockSource);
+        let id: u32 = self.get_property_data(&address)?;
+        let name = self.get_clock_source_name(scope, id)?;
+        Ok(ClockSource { id, name })
+    }
+
+    // The clock source change is asynchronous. The new clock source may not be
+    // applied immediately after this call returns.
+    pub fn set_clock_source(&self, scope: &Scope, id: u32) -> Result<(), Error> {
+        if !self.get_clock_sources(scope)?.iter().any(|s| s.id == id) {
+            return Err(Error::InvalidClockSource);
+        }
+        let address = get_scope_property_address(scope, Property::DeviceClockSource);
+        self.set_property_data(&address, &id)
+    }
+
+    // The devices with the same non-zero clock domain are synchronized to the
+    // same clock. `0` means the clock domain is unknown.
+    pub fn get_clock_domain(&self) -> Result<u32, Error> {
+        let address = get_global_property_address(Property::DeviceClockDomain);
+        self.get_property_data(&address)
+    }
+
+    // The methods below work for any object in the HAL object tree, so the
+    // tree can be walked from the system object via `get_owned_objects`.
+    pub fn get_class(&self) -> Result<ObjectClass, Error> {
+        let address = get_global_property_address(Property::ObjectClass);
+        let class: AudioClassID = self.get_property_data(&address)?;
+        Ok(ObjectClass::from(class))
+    }
+
+    pub fn get_base_class(&self) -> Result<ObjectClass, Error> {
+        let address = get_global_property_address(Property::ObjectBaseClass);
+        let class: AudioClassID = self.get_property_data(&address)?;
+        Ok(ObjectClass::from(class))
+    }
+
+    // The owner of the system object is an unknown object.
+    pub fn get_owner(&self) -> Result<AudioObject, Error> {
+        let address = get_global_property_address(Property::Owner);
+        self.get_property_data(&address)
+    }
+
+    pub fn get_owned_objects(&self) -> Result<Vec<AudioObject>, Error> {
+        get_object_list(self, Property::OwnedObjects)
+    }
+
+    // Take the exclusive access to the device for the current process. It
+    // fails if any process, including the current one, already hogs it.
+    pub fn acquire_hog_mode(&self) -> Result<HogModeGuard, Error> {
+        HogModeGuard::new(self)
+    }
+
+    // Return the pid of the process hogging the device, or `None` if the
+    // device is available to all processes.
+    pub fn get_hog_mode_owner(&self) -> Result<Option<pid_t>, Error> {
+        get_hog_mode_owner(self)
+    }
+
+    // A device is dead once it's removed, e.g., when a USB device is unplugged.
+    pub fn is_alive(&self) -> Result<bool, Error> {
+        let address = get_global_property_address(Property::DeviceIsAlive);
+        let alive: u32 = self.get_property_data(&address)?;
+        Ok(alive != 0)
+    }
+
+    // Call the callback every time the device dies or comes back alive.
+    pub fn add_alive_listener<F>(&self, callback: F) -> Result<PropertyListener, Error>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let address = get_global_property_address(Property::DeviceIsAlive);
+        PropertyListener::new(self.0, address, callback)
+    }
+
+    pub fn get_transport_type(&self) -> Result<TransportType, Error> {
+        let address = get_global_property_address(Property::DeviceTransportType);
+        let transport_type: u32 = self.get_property_data(&address)?;
+        Ok(TransportType::from(transport_type))
+    }
+
     pub fn get_uid(&self) -> Result<String, Error> {
         let address = get_global_property_address(Property::DeviceUID);
         let uid: StringRef = self.get_property_data(&address)?;
@@ -280,10 +627,10 @@ impl AudioObject {
             .map_err(|e| e.into())
     }
 
+    // The overall (min, max) of the supported rates. Not every rate within
+    // this range is necessarily supported. Use `get_supported_rates` to check.
     pub fn get_rate_range(&self, scope: &Scope) -> Result<(f64, f64), Error> {
-        let address = get_scope_property_address(scope, Property::DeviceRateRange);
-
-        let ranges: Vec<AudioValueRange> = self.get_property_array(&address)?;
+        let ranges = self.get_rate_ranges(scope)?;
 
         let mut max = f64::MIN;
         let mut min = f64::MAX;
@@ -296,31 +643,68 @@ impl AudioObject {
         Ok((min, max))
     }
 
+    pub fn get_supported_rates(&self, scope: &Scope) -> Result<SupportedRates, Error> {
+        let ranges = self.get_rate_ranges(scope)?;
+        Ok(SupportedRates::from(ranges.as_slice()))
+    }
+
     pub fn get_device_latency(&self, scope: &Scope) -> Result<u32, Error> {
         let address = get_scope_property_address(scope, Property::DeviceLatency);
         self.get_property_data::<u32>(&address)
             .map_err(|e| e.into())
     }
 
+    // There may be several streams on a device. This returns the latency of
+    // the first stream. Use `get_stream_latencies` to get all of them.
     pub fn get_stream_latency(&self, scope: &Scope) -> Result<u32, Error> {
-        let address = get_scope_property_address(scope, Property::DeviceStreams);
+        let latencies = self.get_stream_latencies(scope)?;
+        latencies.first().cloned().ok_or(Error::WrongScope)
+    }
 
-        let streams: Vec<AudioStream> = self.get_property_array(&address)?;
+    pub fn get_stream_latencies(&self, scope: &Scope) -> Result<Vec<u32>, Error> {
+        let mut latencies = Vec::new();
+        for stream in self.get_streams(scope)? {
+            latencies.push(stream.get_latency(scope)?);
+        }
+        Ok(latencies)
+    }
+
+    pub fn get_safety_offset(&self, scope: &Scope) -> Result<u32, Error> {
+        let address = get_scope_property_address(scope, Property::DeviceSafetyOffset);
+        self.get_property_data::<u32>(&address)
+    }
 
-        // There may be several streams on a device. We use the first stream
-        // to get the latency.
-        // TODO: Is it correct?
-        streams[0].get_latency(scope)
+    pub fn get_buffer_frame_size(&self, scope: &Scope) -> Result<u32, Error> {
+        let address = get_scope_property_address(scope, Property::DeviceBufferFrameSize);
+        self.get_property_data::<u32>(&address)
     }
 
-    // TODO: Merge the get_device_latency and get_stream_latency as
-    //       get_hardware_lantency
+    // Return the latency of each stream in the given scope. See the link below
+    // for how the latency is composed:
     // https://lists.apple.com/archives/coreaudio-api/2017/Jul/msg00035.html
-    // pub fn get_hardware_lantency(
-    //     &self,
-    //     scope: &Scope
-    // ) -> Result<u32, Error> {
-    // }
+    pub fn get_hardware_latency(&self, scope: &Scope) -> Result<Vec<Latency>, Error> {
+        let device = self.get_device_latency(scope)?;
+        let safety_offset = self.get_safety_offset(scope)?;
+        let buffer_frame_size = self.get_buffer_frame_size(scope)?;
+        let latencies = self
+            .get_stream_latencies(scope)?
+            .into_iter()
+            .map(|stream| Latency::new(device, stream, safety_offset, buffer_frame_size))
+            .collect();
+        Ok(latencies)
+    }
+
+    // Return the largest total latency among the streams, in seconds.
+    pub fn get_hardware_latency_in_seconds(&self, scope: &Scope) -> Result<f64, Error> {
+        let rate = self.get_rate(scope)?;
+        let latencies = self.get_hardware_latency(scope)?;
+        let mut max: Option<f64> = None;
+        for latency in latencies.iter() {
+            let seconds = latency.to_seconds(rate).ok_or(Error::InvalidRate(rate))?;
+            max = Some(max.map_or(seconds, |m| get_max(m, seconds)));
+        }
+        max.ok_or(Error::WrongScope)
+    }
 
     pub fn get_buffer_frame_size_range(&self, scope: &Scope) -> Result<(f64, f64), Error> {
         let address = get_scope_property_address(scope, Property::DeviceBufferFrameSizeRange);
@@ -374,11 +758,38 @@ impl AudioObject {
         name.into_string().map_err(Error::ConversionFailed)
     }
 
+    fn get_clock_source_name(&self, scope: &Scope, id: u32) -> Result<String, Error> {
+        let mut id = id;
+        let mut name: StringRef = StringRef::default(); // Create a null string.
+
+        let mut translation = AudioValueTranslation {
+            mInputData: &mut id as *mut u32 as *mut c_void,
+            mInputDataSize: mem::size_of::<u32>() as u32,
+            mOutputData: &mut name as *mut StringRef as *mut c_void,
+            mOutputDataSize: mem::size_of::<StringRef>() as u32,
+        };
+
+        let address = get_scope_property_address(scope, Property::DeviceClockSourceName);
+
+        self.get_property_data_with_ref(&address, &mut translation)?;
+        name.into_string().map_err(Error::ConversionFailed)
+    }
+
+    pub fn get_streams(&self, scope: &Scope) -> Result<Vec<AudioStream>, Error> {
+        let address = get_scope_property_address(scope, Property::DeviceStreams);
+        self.get_property_array(&address)
+    }
+
     pub fn in_scope(&self, scope: &Scope) -> Result<bool, Error> {
         let streams = self.number_of_streams(scope)?;
         Ok(streams > 0)
     }
 
+    fn get_rate_ranges(&self, scope: &Scope) -> Result<Vec<AudioValueRange>, Error> {
+        let address = get_scope_property_address(scope, Property::DeviceRateRange);
+        self.get_property_array(&address)
+    }
+
     fn get_source(&self, scope: &Scope) -> Result<u32, Error> {
         if !self.in_scope(scope)? {
             return Err(Error::WrongScope);
@@ -421,29 +832,25 @@ impl GetPropertyData for AudioObject {}
 impl GetPropertyDataWithRef for AudioObject {}
 impl GetPropertyDataSize for AudioObject {}
 impl GetPropertyVeriableSizedData for AudioObject {}
+impl GetPropertyRawData for AudioObject {}
 impl GetPropertyArray for AudioObject {}
+impl SetPropertyData for AudioObject {}
 
-// AudioStream
+// Utils
 // ============================================================================
-struct AudioStream(AudioStreamID); // AudioStreamID is AudioObjectID
-
-impl AudioStream {
-    fn get_latency(&self, scope: &Scope) -> Result<u32, Error> {
-        let address = get_scope_property_address(scope, Property::StreamLatency);
-        self.get_property_data(&address)
+// Return an empty list instead of an error when there is no object.
+fn get_object_list<O>(object: &O, property: Property) -> Result<Vec<AudioObject>, Error>
+where
+    O: GetObjectId + GetPropertyArray,
+{
+    let address = get_global_property_address(property);
+    match object.get_property_array(&address) {
+        Ok(objects) => Ok(objects),
+        Err(Error::InvalidParameters(audio_object_utils::Error::SizeIsZero)) => Ok(Vec::new()),
+        Err(e) => Err(e),
     }
 }
 
-impl GetObjectId for AudioStream {
-    fn get_id(&self) -> AudioObjectID {
-        self.0
-    }
-}
-
-impl GetPropertyData for AudioStream {}
-
-// Utils
-// ============================================================================
 fn get_min<T: PartialOrd>(a: T, b: T) -> T {
     if a < b {
         a
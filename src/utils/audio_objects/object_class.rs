@@ -0,0 +1,48 @@
+extern crate coreaudio_sys;
+
+use self::coreaudio_sys::AudioClassID;
+
+// ObjectClass
+// ============================================================================
+// The class of an object in the HAL object tree.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjectClass {
+    AggregateDevice,
+    Box,
+    ClockDevice,
+    Control,
+    Device,
+    EndPoint,
+    EndPointDevice,
+    Object,
+    PlugIn,
+    Stream,
+    SubDevice,
+    SystemObject,
+    TransportManager,
+    // The control subclasses (e.g., volume or mute) and the unknown classes.
+    Other(AudioClassID),
+}
+
+// Using the `coreaudio_sys` prefix to match the constants. Otherwise they will
+// be new variables introduced in the `match` block.
+impl From<AudioClassID> for ObjectClass {
+    fn from(class: AudioClassID) -> Self {
+        match class {
+            coreaudio_sys::kAudioAggregateDeviceClassID => ObjectClass::AggregateDevice,
+            coreaudio_sys::kAudioBoxClassID => ObjectClass::Box,
+            coreaudio_sys::kAudioClockDeviceClassID => ObjectClass::ClockDevice,
+            coreaudio_sys::kAudioControlClassID => ObjectClass::Control,
+            coreaudio_sys::kAudioDeviceClassID => ObjectClass::Device,
+            coreaudio_sys::kAudioEndPointClassID => ObjectClass::EndPoint,
+            coreaudio_sys::kAudioEndPointDeviceClassID => ObjectClass::EndPointDevice,
+            coreaudio_sys::kAudioObjectClassID => ObjectClass::Object,
+            coreaudio_sys::kAudioPlugInClassID => ObjectClass::PlugIn,
+            coreaudio_sys::kAudioStreamClassID => ObjectClass::Stream,
+            coreaudio_sys::kAudioSubDeviceClassID => ObjectClass::SubDevice,
+            coreaudio_sys::kAudioSystemObjectClassID => ObjectClass::SystemObject,
+            coreaudio_sys::kAudioTransportManagerClassID => ObjectClass::TransportManager,
+            c => ObjectClass::Other(c),
+        }
+    }
+}
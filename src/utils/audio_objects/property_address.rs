@@ -1,61 +1,184 @@
 extern crate coreaudio_sys;
 
 use self::coreaudio_sys::{
-    kAudioDevicePropertyAvailableNominalSampleRates, kAudioDevicePropertyBufferFrameSizeRange,
-    kAudioDevicePropertyDataSource, kAudioDevicePropertyDataSourceNameForIDCFString,
-    kAudioDevicePropertyDeviceUID, kAudioDevicePropertyLatency,
-    kAudioDevicePropertyNominalSampleRate, kAudioDevicePropertyStreamConfiguration,
-    kAudioDevicePropertyStreams, kAudioHardwarePropertyDefaultInputDevice,
+    kAudioAggregateDevicePropertyFullSubDeviceList, kAudioAggregateDevicePropertyMasterSubDevice,
+    kAudioBoxPropertyAcquired, kAudioBoxPropertyBoxUID, kAudioBoxPropertyDeviceList,
+    kAudioBoxPropertyHasAudio, kAudioBoxPropertyTransportType,
+    kAudioClockDevicePropertyDeviceIsAlive, kAudioClockDevicePropertyDeviceUID,
+    kAudioClockDevicePropertyDomain, kAudioClockDevicePropertyLatency,
+    kAudioClockDevicePropertyNominalSampleRate, kAudioClockDevicePropertyTransportType,
+    kAudioDevicePropertyAvailableNominalSampleRates, kAudioDevicePropertyBufferFrameSize,
+    kAudioDevicePropertyBufferFrameSizeRange, kAudioDevicePropertyClockDomain,
+    kAudioDevicePropertyClockSource, kAudioDevicePropertyClockSourceNameForIDCFString,
+    kAudioDevicePropertyClockSources, kAudioDevicePropertyDataSource,
+    kAudioDevicePropertyDataSourceNameForIDCFString, kAudioDevicePropertyDeviceIsAlive,
+    kAudioDevicePropertyDeviceUID, kAudioDevicePropertyHogMode, kAudioDevicePropertyLatency,
+    kAudioDevicePropertyNominalSampleRate, kAudioDevicePropertyPreferredChannelLayout,
+    kAudioDevicePropertyPreferredChannelsForStereo, kAudioDevicePropertySafetyOffset,
+    kAudioDevicePropertyStreamConfiguration, kAudioDevicePropertyStreams,
+    kAudioDevicePropertyTransportType, kAudioHardwarePropertyBoxList,
+    kAudioHardwarePropertyClockDeviceList, kAudioHardwarePropertyDefaultInputDevice,
     kAudioHardwarePropertyDefaultOutputDevice, kAudioHardwarePropertyDevices,
-    kAudioObjectPropertyElementMaster, kAudioObjectPropertyManufacturer, kAudioObjectPropertyName,
-    kAudioObjectPropertyScopeGlobal, kAudioObjectPropertyScopeInput,
-    kAudioObjectPropertyScopeOutput, kAudioStreamPropertyLatency, AudioObjectPropertyAddress,
-    AudioObjectPropertySelector,
+    kAudioHardwarePropertyPlugInList, kAudioObjectPropertyBaseClass, kAudioObjectPropertyClass,
+    kAudioObjectPropertyElementMaster, kAudioObjectPropertyElementName,
+    kAudioObjectPropertyManufacturer, kAudioObjectPropertyName, kAudioObjectPropertyOwnedObjects,
+    kAudioObjectPropertyOwner, kAudioObjectPropertyScopeGlobal, kAudioObjectPropertyScopeInput,
+    kAudioObjectPropertyScopeOutput, kAudioPlugInPropertyBoxList, kAudioPlugInPropertyBundleID,
+    kAudioPlugInPropertyClockDeviceList, kAudioPlugInPropertyDeviceList,
+    kAudioStreamPropertyAvailablePhysicalFormats, kAudioStreamPropertyAvailableVirtualFormats,
+    kAudioStreamPropertyDirection, kAudioStreamPropertyLatency, kAudioStreamPropertyPhysicalFormat,
+    kAudioStreamPropertyStartingChannel, kAudioStreamPropertyTerminalType,
+    kAudioStreamPropertyVirtualFormat, kAudioSubDevicePropertyDriftCompensation,
+    AudioObjectPropertyAddress, AudioObjectPropertyElement, AudioObjectPropertySelector,
 };
 
 use super::Scope;
 
 pub enum Property {
+    AggregateDeviceMasterSubDevice,
+    AggregateDeviceSubDeviceList,
+    BoxAcquired,
+    BoxDevices,
+    BoxHasAudio,
+    BoxTransportType,
+    BoxUID,
+    Boxes,
+    ClockDeviceDomain,
+    ClockDeviceIsAlive,
+    ClockDeviceLatency,
+    ClockDeviceRate,
+    ClockDeviceTransportType,
+    ClockDeviceUID,
+    ClockDevices,
     DefaultInputDevice,
     DefaultOutputDevice,
+    DeviceBufferFrameSize,
     DeviceBufferFrameSizeRange,
+    DeviceClockDomain,
+    DeviceClockSource,
+    DeviceClockSourceName,
+    DeviceClockSources,
+    DeviceHogMode,
+    DeviceIsAlive,
     DeviceLatency,
     DeviceManufacturer,
     DeviceName,
+    DevicePreferredChannelLayout,
+    DevicePreferredChannelsForStereo,
     DeviceRate,
     DeviceRateRange,
+    DeviceSafetyOffset,
     DeviceSource,
     DeviceSourceName,
     DeviceStreams,
+    DeviceTransportType,
     DeviceUID,
     Devices,
+    ElementName,
+    ObjectBaseClass,
+    ObjectClass,
+    OwnedObjects,
+    Owner,
+    PlugInBoxes,
+    PlugInBundleID,
+    PlugInClockDevices,
+    PlugInDevices,
+    PlugIns,
+    StreamAvailablePhysicalFormats,
+    StreamAvailableVirtualFormats,
     StreamConfiguration,
+    StreamDirection,
     StreamLatency,
+    StreamPhysicalFormat,
+    StreamStartingChannel,
+    StreamTerminalType,
+    StreamVirtualFormat,
+    SubDeviceDriftCompensation,
 }
 
 impl From<Property> for AudioObjectPropertySelector {
     fn from(p: Property) -> Self {
         match p {
+            Property::AggregateDeviceMasterSubDevice => {
+                kAudioAggregateDevicePropertyMasterSubDevice
+            }
+            Property::AggregateDeviceSubDeviceList => {
+                kAudioAggregateDevicePropertyFullSubDeviceList
+            }
+            Property::BoxAcquired => kAudioBoxPropertyAcquired,
+            Property::BoxDevices => kAudioBoxPropertyDeviceList,
+            Property::BoxHasAudio => kAudioBoxPropertyHasAudio,
+            Property::BoxTransportType => kAudioBoxPropertyTransportType,
+            Property::BoxUID => kAudioBoxPropertyBoxUID,
+            Property::Boxes => kAudioHardwarePropertyBoxList,
+            Property::ClockDeviceDomain => kAudioClockDevicePropertyDomain,
+            Property::ClockDeviceIsAlive => kAudioClockDevicePropertyDeviceIsAlive,
+            Property::ClockDeviceLatency => kAudioClockDevicePropertyLatency,
+            Property::ClockDeviceRate => kAudioClockDevicePropertyNominalSampleRate,
+            Property::ClockDeviceTransportType => kAudioClockDevicePropertyTransportType,
+            Property::ClockDeviceUID => kAudioClockDevicePropertyDeviceUID,
+            Property::ClockDevices => kAudioHardwarePropertyClockDeviceList,
             Property::DefaultInputDevice => kAudioHardwarePropertyDefaultInputDevice,
             Property::DefaultOutputDevice => kAudioHardwarePropertyDefaultOutputDevice,
+            Property::DeviceBufferFrameSize => kAudioDevicePropertyBufferFrameSize,
             Property::DeviceBufferFrameSizeRange => kAudioDevicePropertyBufferFrameSizeRange,
+            Property::DeviceClockDomain => kAudioDevicePropertyClockDomain,
+            Property::DeviceClockSource => kAudioDevicePropertyClockSource,
+            Property::DeviceClockSourceName => kAudioDevicePropertyClockSourceNameForIDCFString,
+            Property::DeviceClockSources => kAudioDevicePropertyClockSources,
+            Property::DeviceHogMode => kAudioDevicePropertyHogMode,
+            Property::DeviceIsAlive => kAudioDevicePropertyDeviceIsAlive,
             Property::DeviceLatency => kAudioDevicePropertyLatency,
             Property::DeviceManufacturer => kAudioObjectPropertyManufacturer,
             Property::DeviceName => kAudioObjectPropertyName,
+            Property::DevicePreferredChannelLayout => kAudioDevicePropertyPreferredChannelLayout,
+            Property::DevicePreferredChannelsForStereo => {
+                kAudioDevicePropertyPreferredChannelsForStereo
+            }
             Property::DeviceRate => kAudioDevicePropertyNominalSampleRate,
             Property::DeviceRateRange => kAudioDevicePropertyAvailableNominalSampleRates,
+            Property::DeviceSafetyOffset => kAudioDevicePropertySafetyOffset,
             Property::DeviceSource => kAudioDevicePropertyDataSource,
             Property::DeviceSourceName => kAudioDevicePropertyDataSourceNameForIDCFString,
             Property::DeviceStreams => kAudioDevicePropertyStreams,
+            Property::DeviceTransportType => kAudioDevicePropertyTransportType,
             Property::DeviceUID => kAudioDevicePropertyDeviceUID,
             Property::Devices => kAudioHardwarePropertyDevices,
+            Property::ElementName => kAudioObjectPropertyElementName,
+            Property::ObjectBaseClass => kAudioObjectPropertyBaseClass,
+            Property::ObjectClass => kAudioObjectPropertyClass,
+            Property::OwnedObjects => kAudioObjectPropertyOwnedObjects,
+            Property::Owner => kAudioObjectPropertyOwner,
+            Property::PlugInBoxes => kAudioPlugInPropertyBoxList,
+            Property::PlugInBundleID => kAudioPlugInPropertyBundleID,
+            Property::PlugInClockDevices => kAudioPlugInPropertyClockDeviceList,
+            Property::PlugInDevices => kAudioPlugInPropertyDeviceList,
+            Property::PlugIns => kAudioHardwarePropertyPlugInList,
+            Property::StreamAvailablePhysicalFormats => {
+                kAudioStreamPropertyAvailablePhysicalFormats
+            }
+            Property::StreamAvailableVirtualFormats => kAudioStreamPropertyAvailableVirtualFormats,
             Property::StreamConfiguration => kAudioDevicePropertyStreamConfiguration,
+            Property::StreamDirection => kAudioStreamPropertyDirection,
             Property::StreamLatency => kAudioStreamPropertyLatency,
+            Property::StreamPhysicalFormat => kAudioStreamPropertyPhysicalFormat,
+            Property::StreamStartingChannel => kAudioStreamPropertyStartingChannel,
+            Property::StreamTerminalType => kAudioStreamPropertyTerminalType,
+            Property::StreamVirtualFormat => kAudioStreamPropertyVirtualFormat,
+            Property::SubDeviceDriftCompensation => kAudioSubDevicePropertyDriftCompensation,
         }
     }
 }
 
 pub fn get_scope_property_address(scope: &Scope, p: Property) -> AudioObjectPropertyAddress {
+    get_element_property_address(scope, p, kAudioObjectPropertyElementMaster)
+}
+
+pub fn get_element_property_address(
+    scope: &Scope,
+    p: Property,
+    element: AudioObjectPropertyElement,
+) -> AudioObjectPropertyAddress {
     let scope = if scope == &Scope::Input {
         kAudioObjectPropertyScopeInput
     } else {
@@ -65,7 +188,7 @@ pub fn get_scope_property_address(scope: &Scope, p: Property) -> AudioObjectProp
     AudioObjectPropertyAddress {
         mSelector: p.into(),
         mScope: scope,
-        mElement: kAudioObjectPropertyElementMaster,
+        mElement: element,
     }
 }
 
@@ -0,0 +1,64 @@
+extern crate coreaudio_sys;
+
+use self::coreaudio_sys::{AudioObjectID, AudioObjectPropertyAddress, OSStatus, UInt32};
+use super::audio_object_utils::{add_property_listener, remove_property_listener};
+use super::Error;
+
+use std::os::raw::c_void;
+
+type ListenerCallback = Box<dyn FnMut() + Send>;
+
+// PropertyListener
+// ============================================================================
+// The callback is called on the HAL notification thread every time the
+// property changes, until the listener is dropped. The callback is boxed
+// again so its address passed to the system stays the same when the listener
+// is moved.
+pub struct PropertyListener {
+    id: AudioObjectID,
+    address: AudioObjectPropertyAddress,
+    callback: Box<ListenerCallback>,
+}
+
+impl PropertyListener {
+    pub fn new<F>(
+        id: AudioObjectID,
+        address: AudioObjectPropertyAddress,
+        callback: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let mut listener = PropertyListener {
+            id,
+            address,
+            callback: Box::new(Box::new(callback)),
+        };
+        let data = listener.get_data();
+        add_property_listener(id, &listener.address, Some(on_property_changed), data)?;
+        Ok(listener)
+    }
+
+    fn get_data(&mut self) -> *mut c_void {
+        &mut *self.callback as *mut ListenerCallback as *mut c_void
+    }
+}
+
+impl Drop for PropertyListener {
+    // Don't panic here since the listener may be dropped while unwinding.
+    fn drop(&mut self) {
+        let data = self.get_data();
+        let _ = remove_property_listener(self.id, &self.address, Some(on_property_changed), data);
+    }
+}
+
+extern "C" fn on_property_changed(
+    _id: AudioObjectID,
+    _addresses_count: UInt32,
+    _addresses: *const AudioObjectPropertyAddress,
+    data: *mut c_void,
+) -> OSStatus {
+    let callback = unsafe { &mut *(data as *mut ListenerCallback) };
+    callback();
+    0 // kAudioHardwareNoError.
+}
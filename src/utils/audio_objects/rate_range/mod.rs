@@ -0,0 +1,108 @@
+extern crate coreaudio_sys;
+
+use self::coreaudio_sys::AudioValueRange;
+
+// RateRange
+// ============================================================================
+// The `kAudioDevicePropertyAvailableNominalSampleRates` reports a list of
+// ranges. A range whose minimum equals its maximum is a discrete rate.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RateRange {
+    Discrete(f64),
+    Continuous(f64, f64),
+}
+
+impl RateRange {
+    pub fn get_min(&self) -> f64 {
+        match *self {
+            RateRange::Discrete(rate) => rate,
+            RateRange::Continuous(min, _) => min,
+        }
+    }
+
+    pub fn get_max(&self) -> f64 {
+        match *self {
+            RateRange::Discrete(rate) => rate,
+            RateRange::Continuous(_, max) => max,
+        }
+    }
+
+    pub fn contains(&self, rate: f64) -> bool {
+        rate >= self.get_min() && rate <= self.get_max()
+    }
+
+    // Return the rate within this range that is closest to the given rate.
+    pub fn get_nearest(&self, rate: f64) -> f64 {
+        if rate < self.get_min() {
+            self.get_min()
+        } else if rate > self.get_max() {
+            self.get_max()
+        } else {
+            rate
+        }
+    }
+}
+
+impl<'a> From<&'a AudioValueRange> for RateRange {
+    fn from(range: &'a AudioValueRange) -> Self {
+        if range.mMinimum == range.mMaximum {
+            RateRange::Discrete(range.mMinimum)
+        } else {
+            RateRange::Continuous(range.mMinimum, range.mMaximum)
+        }
+    }
+}
+
+// SupportedRates
+// ============================================================================
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupportedRates(Vec<RateRange>);
+
+impl SupportedRates {
+    pub fn new(ranges: Vec<RateRange>) -> Self {
+        SupportedRates(ranges)
+    }
+
+    pub fn get_ranges(&self) -> &[RateRange] {
+        &self.0
+    }
+
+    pub fn supports_rate(&self, rate: f64) -> bool {
+        self.0.iter().any(|range| range.contains(rate))
+    }
+
+    // Return the supported rate closest to the given rate, or `None` if there
+    // is no supported rate at all. The lower rate wins when two candidates are
+    // equally close.
+    pub fn nearest_supported_rate(&self, rate: f64) -> Option<f64> {
+        let mut nearest: Option<f64> = None;
+        for range in &self.0 {
+            let candidate = range.get_nearest(rate);
+            nearest = match nearest {
+                Some(n) if !is_closer(candidate, n, rate) => Some(n),
+                _ => Some(candidate),
+            };
+        }
+        nearest
+    }
+}
+
+impl<'a> From<&'a [AudioValueRange]> for SupportedRates {
+    fn from(ranges: &'a [AudioValueRange]) -> Self {
+        SupportedRates(ranges.iter().map(RateRange::from).collect())
+    }
+}
+
+// Utils
+// ============================================================================
+fn is_closer(candidate: f64, current: f64, target: f64) -> bool {
+    let candidate_distance = (candidate - target).abs();
+    let current_distance = (current - target).abs();
+    candidate_distance < current_distance
+        || (candidate_distance == current_distance && candidate < current)
+}
+
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod test;
@@ -0,0 +1,100 @@
+use super::*;
+
+fn get_fake_range(min: f64, max: f64) -> AudioValueRange {
+    AudioValueRange {
+        mMinimum: min,
+        mMaximum: max,
+    }
+}
+
+// Tests for Public Functions
+// ============================================================================
+
+// RateRange
+// ------------------------------------
+#[test]
+fn test_rate_range_from_value_range() {
+    assert_eq!(
+        RateRange::from(&get_fake_range(44_100.0, 44_100.0)),
+        RateRange::Discrete(44_100.0)
+    );
+    assert_eq!(
+        RateRange::from(&get_fake_range(8_000.0, 96_000.0)),
+        RateRange::Continuous(8_000.0, 96_000.0)
+    );
+}
+
+#[test]
+fn test_rate_range_contains() {
+    let discrete = RateRange::Discrete(48_000.0);
+    assert!(discrete.contains(48_000.0));
+    assert!(!discrete.contains(44_100.0));
+
+    let continuous = RateRange::Continuous(8_000.0, 96_000.0);
+    assert!(continuous.contains(8_000.0));
+    assert!(continuous.contains(50_000.0));
+    assert!(continuous.contains(96_000.0));
+    assert!(!continuous.contains(192_000.0));
+}
+
+#[test]
+fn test_rate_range_get_nearest() {
+    let continuous = RateRange::Continuous(8_000.0, 96_000.0);
+    assert_eq!(continuous.get_nearest(4_000.0), 8_000.0);
+    assert_eq!(continuous.get_nearest(50_000.0), 50_000.0);
+    assert_eq!(continuous.get_nearest(192_000.0), 96_000.0);
+    assert_eq!(RateRange::Discrete(48_000.0).get_nearest(8_000.0), 48_000.0);
+}
+
+// SupportedRates
+// ------------------------------------
+#[test]
+fn test_supports_rate_with_discrete_rates() {
+    let ranges = [
+        get_fake_range(44_100.0, 44_100.0),
+        get_fake_range(48_000.0, 48_000.0),
+    ];
+    let rates = SupportedRates::from(&ranges[..]);
+    assert_eq!(
+        rates.get_ranges(),
+        &[RateRange::Discrete(44_100.0), RateRange::Discrete(48_000.0)]
+    );
+    assert!(rates.supports_rate(44_100.0));
+    assert!(rates.supports_rate(48_000.0));
+    // The rates between the discrete rates are not supported.
+    assert!(!rates.supports_rate(46_000.0));
+}
+
+#[test]
+fn test_supports_rate_with_mixed_ranges() {
+    let rates = SupportedRates::new(vec![
+        RateRange::Continuous(8_000.0, 48_000.0),
+        RateRange::Discrete(96_000.0),
+    ]);
+    assert!(rates.supports_rate(22_050.0));
+    assert!(rates.supports_rate(96_000.0));
+    assert!(!rates.supports_rate(88_200.0));
+}
+
+#[test]
+fn test_nearest_supported_rate() {
+    let rates = SupportedRates::new(vec![
+        RateRange::Discrete(44_100.0),
+        RateRange::Discrete(48_000.0),
+        RateRange::Continuous(88_200.0, 96_000.0),
+    ]);
+    assert_eq!(rates.nearest_supported_rate(44_100.0), Some(44_100.0));
+    assert_eq!(rates.nearest_supported_rate(8_000.0), Some(44_100.0));
+    assert_eq!(rates.nearest_supported_rate(50_000.0), Some(48_000.0));
+    assert_eq!(rates.nearest_supported_rate(90_000.0), Some(90_000.0));
+    assert_eq!(rates.nearest_supported_rate(192_000.0), Some(96_000.0));
+    // Equally close to 44.1k and 48k. The lower one wins.
+    assert_eq!(rates.nearest_supported_rate(46_050.0), Some(44_100.0));
+}
+
+#[test]
+fn test_nearest_supported_rate_without_rates() {
+    let rates = SupportedRates::new(vec![]);
+    assert!(!rates.supports_rate(48_000.0));
+    assert_eq!(rates.nearest_supported_rate(48_000.0), None);
+}
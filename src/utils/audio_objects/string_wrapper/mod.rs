@@ -1,11 +1,13 @@
 extern crate core_foundation_sys;
 extern crate coreaudio_sys;
 
-use self::core_foundation_sys::base::{Boolean, CFIndex, CFRange, CFRelease};
+use self::core_foundation_sys::base::{kCFAllocatorDefault, Boolean, CFIndex, CFRange, CFRelease};
 use self::core_foundation_sys::string::{
-    kCFStringEncodingUTF8, CFStringGetBytes, CFStringGetLength, CFStringRef,
+    kCFStringEncodingUTF8, CFStringCreateWithBytes, CFStringGetBytes, CFStringGetLength,
+    CFStringRef,
 };
 use std::fmt; // For fmt::{Debug, Formatter, Result}
+use std::mem; // For mem::forget()
 use std::os::raw::c_void;
 use std::ptr; // For ptr::null_mut()
 use std::str::Utf8Error;
@@ -66,6 +68,31 @@ impl StringRef {
     pub fn into_string(self) -> Result<String, Error> {
         self.to_string()
     }
+
+    // Hand the ownership of the inner reference over to the caller. The
+    // caller is responsible for releasing it.
+    pub fn into_raw(self) -> CFStringRef {
+        let string_ref = self.0;
+        mem::forget(self);
+        string_ref
+    }
+}
+
+// The created CFStringRef follows the *Create Rule*, so it will be released
+// when the StringRef is dropped.
+impl<'a> From<&'a str> for StringRef {
+    fn from(s: &'a str) -> Self {
+        let string_ref = unsafe {
+            CFStringCreateWithBytes(
+                kCFAllocatorDefault,
+                s.as_ptr(),
+                s.len() as CFIndex,
+                kCFStringEncodingUTF8,
+                false as Boolean,
+            )
+        };
+        StringRef::new(string_ref)
+    }
 }
 
 impl Drop for StringRef {
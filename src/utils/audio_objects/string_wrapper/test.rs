@@ -5,7 +5,18 @@ use super::*;
 
 // to_string
 // ------------------------------------
-// Skip now ...
+#[test]
+fn test_to_string_with_null_string() {
+    let string_ref = StringRef::default();
+    assert_eq!(string_ref.to_string().unwrap_err(), Error::NullString);
+}
+
+#[test]
+fn test_to_string() {
+    let expected = "Aggregate Device";
+    let string_ref = StringRef::from(expected);
+    assert_eq!(string_ref.to_string().unwrap(), expected);
+}
 
 // Tests for Private Functions
 // ============================================================================
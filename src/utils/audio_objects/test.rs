@@ -1,9 +1,89 @@
 use super::*;
-use utils::get_default_device;
+use utils::{get_default_device, get_devices};
 
 // AudioSystemObject
 // ============================================================================
-// Skip for now ...
+// create_aggregate_device
+// ------------------------------------
+#[test]
+fn test_create_aggregate_device_with_invalid_clock_master() {
+    let system_device = AudioSystemObject::new();
+    let description = AggregateDeviceDescription::new(
+        "Invalid Aggregate Device",
+        "InvalidAggregateDeviceUID",
+        vec![SubDevice::new("SubDeviceUID", false)],
+        "NotASubDeviceUID",
+        true,
+    );
+    assert_eq!(
+        system_device
+            .create_aggregate_device(&description)
+            .err()
+            .unwrap(),
+        Error::InvalidDescription
+    );
+}
+
+// Creating an aggregate device changes the device list seen by the other tests
+// running at the same time. Run it alone by `cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn test_create_aggregate_device() {
+    let input = get_default_device(&Scope::Input);
+    let output = get_default_device(&Scope::Output);
+    if input.is_err() || output.is_err() {
+        return;
+    }
+
+    let input_uid = input.unwrap().get_uid().unwrap();
+    let output_uid = output.unwrap().get_uid().unwrap();
+    let mut sub_devices = vec![SubDevice::new(&output_uid, false)];
+    if input_uid != output_uid {
+        sub_devices.push(SubDevice::new(&input_uid, true));
+    }
+    let description = AggregateDeviceDescription::new(
+        "Test Aggregate Device",
+        "TestAggregateDeviceUID",
+        sub_devices.clone(),
+        &output_uid,
+        true,
+    );
+
+    let system_device = AudioSystemObject::new();
+    let device = system_device.create_aggregate_device(&description).unwrap();
+    assert!(device.is_valid());
+    assert_eq!(device.get_uid().unwrap(), description.uid);
+    assert_eq!(device.get_clock_master().unwrap(), output_uid);
+    let uids: Vec<String> = device
+        .get_sub_devices()
+        .unwrap()
+        .into_iter()
+        .map(|sub_device| sub_device.uid)
+        .collect();
+    let expected: Vec<String> = sub_devices.into_iter().map(|s| s.uid).collect();
+    assert_eq!(uids, expected);
+}
+
+// Latency
+// ============================================================================
+#[test]
+fn test_latency() {
+    let latency = Latency::new(10, 20, 30, 512);
+    assert_eq!(latency.get_frames(), 572);
+    assert_eq!(latency.to_seconds(44_100.0), Some(572.0 / 44_100.0));
+
+    let zero = Latency::new(0, 0, 0, 0);
+    assert_eq!(zero.get_frames(), 0);
+    assert_eq!(zero.to_seconds(48_000.0), Some(0.0));
+}
+
+#[test]
+fn test_latency_with_invalid_rate() {
+    let latency = Latency::new(10, 20, 30, 512);
+    assert_eq!(latency.to_seconds(0.0), None);
+    assert_eq!(latency.to_seconds(-44_100.0), None);
+    assert_eq!(latency.to_seconds(f64::NAN), None);
+}
 
 // AudioObject
 // ============================================================================
@@ -290,6 +370,340 @@ fn test_number_of_streams() {
     }
 }
 
+// get_preferred_channel_layout
+// ------------------------------------
+#[test]
+fn test_get_preferred_channel_layout() {
+    if let Ok(device) = get_default_device(&Scope::Output) {
+        if let Ok(layout) = device.get_preferred_channel_layout(&Scope::Output) {
+            assert!(layout.channel_count() > 0);
+        }
+    }
+}
+
+// get_channel_names
+// ------------------------------------
+#[test]
+fn test_get_channel_names() {
+    if let Ok(device) = get_default_device(&Scope::Input) {
+        let names = device.get_channel_names(&Scope::Input).unwrap();
+        assert_eq!(
+            names.len() as u32,
+            device.get_channel_count(&Scope::Input).unwrap()
+        );
+    }
+
+    if let Ok(device) = get_default_device(&Scope::Output) {
+        let names = device.get_channel_names(&Scope::Output).unwrap();
+        assert_eq!(
+            names.len() as u32,
+            device.get_channel_count(&Scope::Output).unwrap()
+        );
+    }
+}
+
+// get_class
+// ------------------------------------
+#[test]
+fn test_object_class() {
+    assert_eq!(
+        ObjectClass::from(coreaudio_sys::kAudioDeviceClassID),
+        ObjectClass::Device
+    );
+    assert_eq!(
+        ObjectClass::from(coreaudio_sys::kAudioPlugInClassID),
+        ObjectClass::PlugIn
+    );
+    assert_eq!(ObjectClass::from(0x1234), ObjectClass::Other(0x1234));
+}
+
+#[test]
+fn test_get_class_with_invalid_id() {
+    let unknown_device = AudioObject::new(kAudioObjectUnknown);
+    assert_eq!(
+        unknown_device.get_class().unwrap_err(),
+        Error::InvalidParameters(audio_object_utils::Error::BadObject)
+    );
+}
+
+#[test]
+fn test_get_class() {
+    let system = AudioSystemObject::new().to_object();
+    assert_eq!(system.get_class().unwrap(), ObjectClass::SystemObject);
+    for scope in vec![Scope::Input, Scope::Output] {
+        if let Ok(device) = get_default_device(&scope) {
+            assert_eq!(device.get_base_class().unwrap(), ObjectClass::Object);
+            let class = device.get_class().unwrap();
+            assert!(class == ObjectClass::Device || class == ObjectClass::AggregateDevice);
+        }
+    }
+}
+
+// get_owner
+// ------------------------------------
+#[test]
+fn test_get_owner() {
+    for scope in vec![Scope::Input, Scope::Output] {
+        if let Ok(device) = get_default_device(&scope) {
+            // A device is owned by the plug-in providing it.
+            let owner = device.get_owner().unwrap();
+            assert_eq!(owner.get_class().unwrap(), ObjectClass::PlugIn);
+            assert!(owner.get_owned_objects().unwrap().contains(&device));
+        }
+    }
+}
+
+// acquire_hog_mode
+// ------------------------------------
+#[test]
+fn test_acquire_hog_mode_with_invalid_id() {
+    let unknown_device = AudioObject::new(kAudioObjectUnknown);
+    assert_eq!(
+        unknown_device.acquire_hog_mode().unwrap_err(),
+        Error::InvalidParameters(audio_object_utils::Error::BadObject)
+    );
+}
+
+// Hogging the default device interrupts the other processes using it.
+#[test]
+#[ignore]
+fn test_acquire_hog_mode() {
+    let device = get_default_device(&Scope::Output).unwrap();
+    assert_eq!(device.get_hog_mode_owner().unwrap(), None);
+    {
+        let guard = device.acquire_hog_mode().unwrap();
+        assert_eq!(guard.get_device(), &device);
+        let pid = hog_mode::get_current_pid();
+        assert_eq!(device.get_hog_mode_owner().unwrap(), Some(pid));
+        assert_eq!(
+            device.acquire_hog_mode().unwrap_err(),
+            Error::DeviceHogged(pid)
+        );
+    }
+    assert_eq!(device.get_hog_mode_owner().unwrap(), None);
+}
+
+#[test]
+#[ignore]
+fn test_release_hog_mode_on_panic() {
+    use std::panic;
+    let device = get_default_device(&Scope::Output).unwrap();
+    let hogged = device.clone();
+    let result = panic::catch_unwind(move || {
+        let _guard = hogged.acquire_hog_mode().unwrap();
+        panic!("Drop the guard while unwinding");
+    });
+    assert!(result.is_err());
+    assert_eq!(device.get_hog_mode_owner().unwrap(), None);
+}
+
+// is_alive
+// ------------------------------------
+#[test]
+fn test_is_alive_with_invalid_id() {
+    let unknown_device = AudioObject::new(kAudioObjectUnknown);
+    assert_eq!(
+        unknown_device.is_alive().unwrap_err(),
+        Error::InvalidParameters(audio_object_utils::Error::BadObject)
+    );
+}
+
+#[test]
+fn test_is_alive() {
+    for device in get_devices(&Scope::Output).unwrap_or_default() {
+        assert!(device.is_alive().unwrap());
+    }
+}
+
+// add_alive_listener
+// ------------------------------------
+#[test]
+fn test_add_alive_listener_with_invalid_id() {
+    let unknown_device = AudioObject::new(kAudioObjectUnknown);
+    assert_eq!(
+        unknown_device.add_alive_listener(|| {}).err(),
+        Some(Error::InvalidParameters(
+            audio_object_utils::Error::BadObject
+        ))
+    );
+}
+
+#[test]
+fn test_add_alive_listener() {
+    if let Ok(device) = get_default_device(&Scope::Output) {
+        assert!(device.add_alive_listener(|| {}).is_ok());
+    }
+}
+
+// get_clock_sources
+// ------------------------------------
+#[test]
+fn test_get_clock_sources() {
+    for scope in vec![Scope::Input, Scope::Output] {
+        if let Ok(device) = get_default_device(&scope) {
+            let sources = device.get_clock_sources(&scope).unwrap();
+            if sources.is_empty() {
+                continue;
+            }
+            let current = device.get_clock_source(&scope).unwrap();
+            assert!(sources.contains(&current));
+        }
+    }
+}
+
+#[test]
+fn test_set_clock_source_with_invalid_source() {
+    for scope in vec![Scope::Input, Scope::Output] {
+        if let Ok(device) = get_default_device(&scope) {
+            let sources = device.get_clock_sources(&scope).unwrap();
+            let invalid = sources.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+            assert_eq!(
+                device.set_clock_source(&scope, invalid).unwrap_err(),
+                Error::InvalidClockSource
+            );
+        }
+    }
+}
+
+// get_supported_rates
+// ------------------------------------
+#[test]
+fn test_get_supported_rates() {
+    for scope in vec![Scope::Input, Scope::Output] {
+        if let Ok(device) = get_default_device(&scope) {
+            let rates = device.get_supported_rates(&scope).unwrap();
+            let rate = device.get_rate(&scope).unwrap();
+            assert!(rates.supports_rate(rate));
+            assert_eq!(rates.nearest_supported_rate(rate), Some(rate));
+            let (min, max) = device.get_rate_range(&scope).unwrap();
+            for range in rates.get_ranges() {
+                assert!(range.get_min() >= min && range.get_max() <= max);
+            }
+        }
+    }
+}
+
+// get_hardware_latency
+// ------------------------------------
+#[test]
+fn test_get_hardware_latency_with_invalid_id() {
+    let unknown_device = AudioObject::new(kAudioObjectUnknown);
+    assert_eq!(
+        unknown_device
+            .get_hardware_latency(&Scope::Output)
+            .unwrap_err(),
+        Error::InvalidParameters(audio_object_utils::Error::BadObject)
+    );
+}
+
+#[test]
+fn test_get_hardware_latency() {
+    for scope in vec![Scope::Input, Scope::Output] {
+        if let Ok(device) = get_default_device(&scope) {
+            let latencies = device.get_hardware_latency(&scope).unwrap();
+            let streams = device.get_streams(&scope).unwrap();
+            assert_eq!(latencies.len(), streams.len());
+            let buffer_frame_size = device.get_buffer_frame_size(&scope).unwrap();
+            for latency in &latencies {
+                assert_eq!(latency.buffer_frame_size, buffer_frame_size);
+                assert!(latency.get_frames() >= buffer_frame_size);
+            }
+            assert!(device.get_hardware_latency_in_seconds(&scope).unwrap() > 0.0);
+        }
+    }
+}
+
+// get_streams
+// ------------------------------------
+#[test]
+fn test_get_streams() {
+    if let Ok(device) = get_default_device(&Scope::Input) {
+        let streams = device.get_streams(&Scope::Input).unwrap();
+        assert!(!streams.is_empty());
+        for stream in streams {
+            assert_eq!(stream.get_direction().unwrap(), Scope::Input);
+        }
+    }
+
+    if let Ok(device) = get_default_device(&Scope::Output) {
+        let streams = device.get_streams(&Scope::Output).unwrap();
+        assert!(!streams.is_empty());
+        for stream in streams {
+            assert_eq!(stream.get_direction().unwrap(), Scope::Output);
+        }
+    }
+}
+
 // AudioStream
 // ============================================================================
-// Skip for now ...
+fn get_fake_format(rate: f64, bits: u32) -> StreamFormat {
+    StreamFormat {
+        rate,
+        format_id: coreaudio_sys::kAudioFormatLinearPCM,
+        flags: coreaudio_sys::kAudioFormatFlagIsSignedInteger
+            | coreaudio_sys::kAudioFormatFlagIsPacked,
+        bytes_per_packet: bits / 8 * 2,
+        frames_per_packet: 1,
+        bytes_per_frame: bits / 8 * 2,
+        channels: 2,
+        bits_per_channel: bits,
+    }
+}
+
+// StreamFormat
+// ------------------------------------
+#[test]
+fn test_stream_format_conversion() {
+    let format = get_fake_format(48_000.0, 24);
+    assert!(format.is_linear_pcm());
+    assert!(format.is_signed_integer());
+    assert!(!format.is_float());
+    assert!(format.is_interleaved());
+    assert_eq!(StreamFormat::from(&format.to_description()), format);
+}
+
+// StreamFormatRange
+// ------------------------------------
+#[test]
+fn test_stream_format_range_contains() {
+    let range = StreamFormatRange {
+        format: get_fake_format(0.0, 24),
+        rate_range: (44_100.0, 96_000.0),
+    };
+    assert!(range.contains(&get_fake_format(48_000.0, 24)));
+    assert!(range.contains(&get_fake_format(0.0, 24)));
+    assert!(!range.contains(&get_fake_format(192_000.0, 24)));
+    assert!(!range.contains(&get_fake_format(48_000.0, 16)));
+
+    let discrete = StreamFormatRange {
+        format: get_fake_format(44_100.0, 16),
+        rate_range: (44_100.0, 44_100.0),
+    };
+    assert!(discrete.contains(&get_fake_format(44_100.0, 16)));
+    assert!(!discrete.contains(&get_fake_format(48_000.0, 16)));
+}
+
+// TerminalType
+// ------------------------------------
+#[test]
+fn test_terminal_type() {
+    assert_eq!(
+        TerminalType::from(coreaudio_sys::kAudioStreamTerminalTypeSpeaker),
+        TerminalType::Speaker
+    );
+    assert_eq!(TerminalType::from(0x0301), TerminalType::Other(0x0301));
+}
+
+// get_physical_format
+// ------------------------------------
+#[test]
+fn test_get_physical_format() {
+    if let Ok(device) = get_default_device(&Scope::Output) {
+        for stream in device.get_streams(&Scope::Output).unwrap() {
+            let format = stream.get_physical_format().unwrap();
+            assert!(format.channels > 0);
+            assert!(stream.is_physical_format_supported(&format).unwrap());
+            assert!(!stream.get_available_virtual_formats().unwrap().is_empty());
+        }
+    }
+}
@@ -0,0 +1,47 @@
+extern crate coreaudio_sys;
+
+// TransportType
+// ============================================================================
+// How the device is connected to the system.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransportType {
+    AVB,
+    Aggregate,
+    AirPlay,
+    Bluetooth,
+    BluetoothLE,
+    BuiltIn,
+    DisplayPort,
+    FireWire,
+    HDMI,
+    PCI,
+    Thunderbolt,
+    USB,
+    Unknown,
+    Virtual,
+    Other(u32),
+}
+
+// Using the `coreaudio_sys` prefix to match the constants. Otherwise they will
+// be new variables introduced in the `match` block.
+impl From<u32> for TransportType {
+    fn from(transport_type: u32) -> Self {
+        match transport_type {
+            coreaudio_sys::kAudioDeviceTransportTypeAVB => TransportType::AVB,
+            coreaudio_sys::kAudioDeviceTransportTypeAggregate => TransportType::Aggregate,
+            coreaudio_sys::kAudioDeviceTransportTypeAirPlay => TransportType::AirPlay,
+            coreaudio_sys::kAudioDeviceTransportTypeBluetooth => TransportType::Bluetooth,
+            coreaudio_sys::kAudioDeviceTransportTypeBluetoothLE => TransportType::BluetoothLE,
+            coreaudio_sys::kAudioDeviceTransportTypeBuiltIn => TransportType::BuiltIn,
+            coreaudio_sys::kAudioDeviceTransportTypeDisplayPort => TransportType::DisplayPort,
+            coreaudio_sys::kAudioDeviceTransportTypeFireWire => TransportType::FireWire,
+            coreaudio_sys::kAudioDeviceTransportTypeHDMI => TransportType::HDMI,
+            coreaudio_sys::kAudioDeviceTransportTypePCI => TransportType::PCI,
+            coreaudio_sys::kAudioDeviceTransportTypeThunderbolt => TransportType::Thunderbolt,
+            coreaudio_sys::kAudioDeviceTransportTypeUSB => TransportType::USB,
+            coreaudio_sys::kAudioDeviceTransportTypeUnknown => TransportType::Unknown,
+            coreaudio_sys::kAudioDeviceTransportTypeVirtual => TransportType::Virtual,
+            t => TransportType::Other(t),
+        }
+    }
+}
@@ -1,7 +1,15 @@
 mod audio_objects;
 
 use self::audio_objects::AudioSystemObject;
-pub use self::audio_objects::{AudioObject, GetObjectId, Scope};
+pub use self::audio_objects::{
+    AggregateDevice, AggregateDeviceDescription, AudioBox, AudioClockDevice, AudioObject,
+    AudioPlugIn, AudioStream, ChannelDescription, ChannelLabel, ChannelLayout, ClockDomain,
+    ClockSource, DeviceQuery, GetObjectId, HogModeGuard, Latency, ObjectClass, PropertyListener,
+    RateRange, Scope, StreamFormat, StreamFormatRange, SubDevice, SupportedRates, TerminalType,
+    TransportType,
+};
+
+use std::cmp::Ordering;
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -24,6 +32,26 @@ pub fn get_default_device(scope: &Scope) -> Result<AudioObject, Error> {
         .map_err(|e| e.into())
 }
 
+pub fn add_default_device_listener<F>(scope: &Scope, callback: F) -> Result<PropertyListener, Error>
+where
+    F: FnMut() + Send + 'static,
+{
+    let system_device = AudioSystemObject::new();
+    system_device
+        .add_default_device_listener(scope, callback)
+        .map_err(|e| e.into())
+}
+
+pub fn add_devices_listener<F>(callback: F) -> Result<PropertyListener, Error>
+where
+    F: FnMut() + Send + 'static,
+{
+    let system_device = AudioSystemObject::new();
+    system_device
+        .add_devices_listener(callback)
+        .map_err(|e| e.into())
+}
+
 pub fn get_devices(scope: &Scope) -> Result<Vec<AudioObject>, Error> {
     let system_device = AudioSystemObject::new();
     system_device.get_devices(scope).map_err(|e| e.into())
@@ -34,6 +62,16 @@ pub fn get_all_devices() -> Result<Vec<AudioObject>, Error> {
     system_device.get_all_devices().map_err(|e| e.into())
 }
 
+pub fn find_devices<F>(query: &DeviceQuery, compare: F) -> Result<Vec<AudioObject>, Error>
+where
+    F: FnMut(&AudioObject, &AudioObject) -> Ordering,
+{
+    let system_device = AudioSystemObject::new();
+    system_device
+        .find_devices(query, compare)
+        .map_err(|e| e.into())
+}
+
 pub fn set_default_device(device: &AudioObject, scope: &Scope) -> Result<(), Error> {
     let system_device = AudioSystemObject::new();
     system_device
@@ -41,6 +79,42 @@ pub fn set_default_device(device: &AudioObject, scope: &Scope) -> Result<(), Err
         .map_err(|e| e.into())
 }
 
+pub fn create_aggregate_device(
+    description: &AggregateDeviceDescription,
+) -> Result<AggregateDevice, Error> {
+    let system_device = AudioSystemObject::new();
+    system_device
+        .create_aggregate_device(description)
+        .map_err(|e| e.into())
+}
+
+pub fn get_system_object() -> AudioObject {
+    let system_device = AudioSystemObject::new();
+    system_device.to_object()
+}
+
+pub fn get_plug_ins() -> Result<Vec<AudioPlugIn>, Error> {
+    let system_device = AudioSystemObject::new();
+    system_device.get_plug_ins().map_err(|e| e.into())
+}
+
+pub fn get_boxes() -> Result<Vec<AudioBox>, Error> {
+    let system_device = AudioSystemObject::new();
+    system_device.get_boxes().map_err(|e| e.into())
+}
+
+pub fn get_clock_devices() -> Result<Vec<AudioClockDevice>, Error> {
+    let system_device = AudioSystemObject::new();
+    system_device.get_clock_devices().map_err(|e| e.into())
+}
+
+pub fn get_devices_by_clock_domain() -> Result<Vec<ClockDomain>, Error> {
+    let system_device = AudioSystemObject::new();
+    system_device
+        .get_devices_by_clock_domain()
+        .map_err(|e| e.into())
+}
+
 // Tests
 // ============================================================================
 #[cfg(test)]
@@ -17,6 +17,46 @@ fn test_get_default_device() {
     }
 }
 
+// add_default_device_listener
+// ------------------------------------
+#[test]
+fn test_add_default_device_listener() {
+    for scope in vec![Scope::Input, Scope::Output] {
+        let listener = add_default_device_listener(&scope, || {});
+        assert!(listener.is_ok());
+    }
+}
+
+// The listener is called when the default device changes.
+#[test]
+#[ignore]
+fn test_default_device_listener_with_changed_device() {
+    use std::sync::mpsc;
+
+    let default_device = get_default_device(&Scope::Output).unwrap();
+    let devices = get_devices(&Scope::Output).unwrap();
+    let device = match devices.into_iter().find(|device| device != &default_device) {
+        Some(device) => device,
+        None => return, // Need at least two output devices.
+    };
+    let (sender, receiver) = mpsc::channel();
+    let _listener = add_default_device_listener(&Scope::Output, move || {
+        let _ = sender.send(());
+    })
+    .unwrap();
+    set_default_device(&device, &Scope::Output).unwrap();
+    let changed = receiver.recv_timeout(std::time::Duration::from_secs(1));
+    set_default_device(&default_device, &Scope::Output).unwrap();
+    assert!(changed.is_ok());
+}
+
+// add_devices_listener
+// ------------------------------------
+#[test]
+fn test_add_devices_listener() {
+    assert!(add_devices_listener(|| {}).is_ok());
+}
+
 // get_device_ids
 // ------------------------------------
 #[test]
@@ -131,3 +171,121 @@ fn test_set_default_device() {
     test_change_default_device(&Scope::Input);
     test_change_default_device(&Scope::Output);
 }
+
+// get_devices_by_clock_domain
+// ------------------------------------
+#[test]
+fn test_get_devices_by_clock_domain() {
+    let domains = get_devices_by_clock_domain().unwrap();
+    let mut devices = Vec::new();
+    for domain in &domains {
+        assert!(!domain.devices.is_empty());
+        for device in &domain.devices {
+            assert_eq!(device.get_clock_domain().unwrap_or(0), domain.id);
+            devices.push(device.clone());
+        }
+    }
+    // Every device belongs to exactly one group.
+    assert_eq!(devices.len(), get_all_devices().unwrap().len());
+}
+
+// find_devices
+// ------------------------------------
+#[test]
+fn test_find_devices() {
+    for scope in vec![Scope::Input, Scope::Output] {
+        let query = DeviceQuery::new(scope.clone());
+        let devices = find_devices(&query, |_, _| Ordering::Equal).unwrap();
+        assert_eq!(devices, get_devices(&scope).unwrap());
+    }
+}
+
+#[test]
+fn test_find_devices_with_criteria() {
+    for scope in vec![Scope::Input, Scope::Output] {
+        if let Ok(device) = get_default_device(&scope) {
+            let rate = device.get_rate(&scope).unwrap();
+            let channels = device.get_channel_count(&scope).unwrap();
+            let query = DeviceQuery::new(scope.clone())
+                .min_channels(channels)
+                .supports_rate(rate)
+                .transport_type(device.get_transport_type().unwrap())
+                .uid(&device.get_uid().unwrap());
+            let devices = find_devices(&query, |_, _| Ordering::Equal).unwrap();
+            assert!(devices.contains(&device));
+
+            let query = DeviceQuery::new(scope.clone()).min_channels(channels + 1);
+            let devices = find_devices(&query, |_, _| Ordering::Equal).unwrap();
+            assert!(!devices.contains(&device));
+        }
+    }
+}
+
+#[test]
+fn test_find_devices_ranked() {
+    // Prefer the devices with more channels.
+    let scope = Scope::Output;
+    let query = DeviceQuery::new(scope.clone());
+    let devices = find_devices(&query, |a, b| {
+        let a = a.get_channel_count(&scope).unwrap_or(0);
+        let b = b.get_channel_count(&scope).unwrap_or(0);
+        b.cmp(&a)
+    })
+    .unwrap();
+    for pair in devices.windows(2) {
+        assert!(
+            pair[0].get_channel_count(&scope).unwrap_or(0)
+                >= pair[1].get_channel_count(&scope).unwrap_or(0)
+        );
+    }
+}
+
+// get_plug_ins
+// ------------------------------------
+#[test]
+fn test_get_plug_ins() {
+    let plug_ins = get_plug_ins().unwrap();
+    for plug_in in &plug_ins {
+        assert_eq!(plug_in.get_class().unwrap(), ObjectClass::PlugIn);
+        assert!(!plug_in.get_bundle_id().unwrap().is_empty());
+    }
+    // Every device is provided by one of the plug-ins.
+    for device in get_all_devices().unwrap() {
+        assert!(plug_ins
+            .iter()
+            .any(|plug_in| plug_in.get_devices().unwrap().contains(&device)));
+    }
+}
+
+// get_boxes
+// ------------------------------------
+#[test]
+fn test_get_boxes() {
+    for audio_box in get_boxes().unwrap() {
+        assert_eq!(audio_box.get_class().unwrap(), ObjectClass::Box);
+        assert!(audio_box.get_uid().is_ok());
+        assert!(audio_box.is_acquired().is_ok());
+    }
+}
+
+// get_clock_devices
+// ------------------------------------
+#[test]
+fn test_get_clock_devices() {
+    for clock_device in get_clock_devices().unwrap() {
+        assert_eq!(clock_device.get_class().unwrap(), ObjectClass::ClockDevice);
+        assert!(clock_device.get_uid().is_ok());
+    }
+}
+
+// get_system_object
+// ------------------------------------
+#[test]
+fn test_get_system_object() {
+    let system = get_system_object();
+    assert_eq!(system.get_class().unwrap(), ObjectClass::SystemObject);
+    let owned = system.get_owned_objects().unwrap();
+    for plug_in in get_plug_ins().unwrap() {
+        assert!(owned.contains(&*plug_in));
+    }
+}